@@ -0,0 +1,278 @@
+use std::ops::{BitAnd, BitOr, Sub};
+use crate::coord::{Direction, Point};
+use crate::grid::{Cell, Grid};
+
+// One bit per cell, row-major, with every row starting on a fresh word so rows can be
+// shifted and masked a word at a time
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    height: usize,
+    width: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(height: usize, width: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        BitGrid { height, width, words_per_row, words: vec![0; height * words_per_row] }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        let mut bits = BitGrid::new(grid.height(), grid.width());
+        for (cell, value) in grid.cells() {
+            if predicate(value) {
+                bits.set(cell);
+            }
+        }
+        bits
+    }
+
+    pub fn from_cells(height: usize, width: usize, cells: impl IntoIterator<Item = Cell>) -> Self {
+        let mut bits = BitGrid::new(height, width);
+        for cell in cells {
+            bits.set(cell);
+        }
+        bits
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn contains(&self, (r, c): Cell) -> bool {
+        r < self.height && c < self.width
+    }
+
+    fn index(&self, (r, c): Cell) -> (usize, u64) {
+        (r * self.words_per_row + c / 64, 1 << (c % 64))
+    }
+
+    // Cells outside the grid are never set
+    pub fn get(&self, cell: Cell) -> bool {
+        if !self.contains(cell) {
+            return false;
+        }
+        let (word, bit) = self.index(cell);
+        self.words[word] & bit != 0
+    }
+
+    pub fn at(&self, p: Point) -> Option<bool> {
+        p.to_cell_within(self.height, self.width).map(|cell| self.get(cell))
+    }
+
+    pub fn set(&mut self, cell: Cell) {
+        self.assign(cell, true);
+    }
+
+    pub fn clear(&mut self, cell: Cell) {
+        self.assign(cell, false);
+    }
+
+    pub fn assign(&mut self, cell: Cell, value: bool) {
+        if !self.contains(cell) {
+            panic!("Cell {:?} is outside a {}x{} grid", cell, self.height, self.width);
+        }
+        let (word, bit) = self.index(cell);
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let r = i / self.words_per_row;
+            let base = (i % self.words_per_row) * 64;
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some((r, base + bit))
+            })
+        })
+    }
+
+    // Bit c of the row, least significant word first
+    pub fn row_mask(&self, r: usize) -> &[u64] {
+        &self.words[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    // Bit r of the column, least significant word first
+    pub fn column_mask(&self, c: usize) -> Vec<u64> {
+        let mut mask = vec![0; self.height.div_ceil(64)];
+        for r in (0..self.height).filter(|&r| self.get((r, c))) {
+            mask[r / 64] |= 1 << (r % 64);
+        }
+        mask
+    }
+
+    // Every set cell moves one step in the direction; cells pushed off the edge are lost
+    pub fn shifted(&self, direction: Direction) -> BitGrid {
+        let mut result = BitGrid::new(self.height, self.width);
+        let n = self.words_per_row;
+        if n == 0 {
+            return result;
+        }
+        match direction {
+            Direction::North if self.height > 0 => {
+                result.words[..(self.height - 1) * n].copy_from_slice(&self.words[n..]);
+            }
+            Direction::South if self.height > 0 => {
+                result.words[n..].copy_from_slice(&self.words[..(self.height - 1) * n]);
+            }
+            Direction::East => {
+                for (to, from) in result.words.chunks_mut(n).zip(self.words.chunks(n)) {
+                    for i in 0..n {
+                        let carry = if i > 0 { from[i - 1] >> 63 } else { 0 };
+                        to[i] = (from[i] << 1) | carry;
+                    }
+                }
+                result.trim_rows();
+            }
+            Direction::West => {
+                for (to, from) in result.words.chunks_mut(n).zip(self.words.chunks(n)) {
+                    for i in 0..n {
+                        let carry = if i + 1 < n { from[i + 1] << 63 } else { 0 };
+                        to[i] = (from[i] >> 1) | carry;
+                    }
+                }
+            }
+            _ => {}
+        }
+        result
+    }
+
+    // Clears the padding bits past the last column
+    fn trim_rows(&mut self) {
+        if self.width.is_multiple_of(64) {
+            return;
+        }
+        let keep = (1u64 << (self.width % 64)) - 1;
+        for row in self.words.chunks_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= keep;
+        }
+    }
+
+    fn combine(&self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) -> BitGrid {
+        assert_eq!((self.height, self.width), (other.height, other.width), "Grid sizes differ");
+        BitGrid {
+            words: self.words.iter().zip(&other.words).map(|(&a, &b)| f(a, b)).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: &BitGrid) -> BitGrid {
+        self.combine(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: &BitGrid) -> BitGrid {
+        self.combine(rhs, |a, b| a | b)
+    }
+}
+
+impl Sub for &BitGrid {
+    type Output = BitGrid;
+
+    fn sub(self, rhs: &BitGrid) -> BitGrid {
+        self.combine(rhs, |a, b| a & !b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod bits {
+        use crate::bitgrid::BitGrid;
+        use crate::coord::Point;
+        use crate::grid::Grid;
+
+        #[test]
+        fn set_test_clear() {
+            let mut bits = BitGrid::new(3, 100);
+            bits.set((1, 70));
+            bits.set((2, 0));
+            assert!(bits.get((1, 70)));
+            assert!(!bits.get((1, 69)));
+            assert!(!bits.get((5, 5)));
+            assert_eq!(Some(true), bits.at(Point::new(70, 1)));
+            assert_eq!(None, bits.at(Point::new(-1, 1)));
+            bits.clear((1, 70));
+            assert_eq!(vec![(2, 0)], bits.iter().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn masks() {
+            let grid = Grid::parse("#..#\n.#..\n##..", |c| c == '#');
+            let bits = BitGrid::from_grid(&grid, |&wall| wall);
+            assert_eq!(5, bits.count_ones());
+            assert_eq!(&[0b1001], bits.row_mask(0));
+            assert_eq!(vec![0b110], bits.column_mask(1));
+        }
+
+        #[test]
+        fn set_operations() {
+            let a = BitGrid::from_cells(2, 2, [(0, 0), (0, 1)]);
+            let b = BitGrid::from_cells(2, 2, [(0, 1), (1, 1)]);
+            assert_eq!(vec![(0, 1)], (&a & &b).iter().collect::<Vec<_>>());
+            assert_eq!(3, (&a | &b).count_ones());
+            assert_eq!(vec![(0, 0)], (&a - &b).iter().collect::<Vec<_>>());
+        }
+    }
+
+    mod shifts {
+        use crate::bitgrid::BitGrid;
+        use crate::coord::Direction::{self, *};
+
+        #[test]
+        fn across_words() {
+            let bits = BitGrid::from_cells(2, 70, [(0, 63), (1, 69), (1, 0)]);
+            let cells = |b: BitGrid| b.iter().collect::<Vec<_>>();
+            assert_eq!(vec![(0, 64), (1, 1)], cells(bits.shifted(East)));
+            assert_eq!(vec![(0, 62), (1, 68)], cells(bits.shifted(West)));
+            assert_eq!(vec![(1, 63)], cells(bits.shifted(South)));
+            assert_eq!(vec![(0, 0), (0, 69)], cells(bits.shifted(North)));
+        }
+
+        #[test]
+        fn boundary_count() {
+            // a plus shape has 12 unit edges on its boundary
+            let plus = BitGrid::from_cells(3, 3, [(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)]);
+            let perimeter: usize = Direction::ALL.into_iter()
+                .map(|d| (&plus - &plus.shifted(d.reverse())).count_ones())
+                .sum();
+            assert_eq!(12, perimeter);
+        }
+
+        #[test]
+        fn zero_width() {
+            let empty = BitGrid::new(3, 0);
+            for direction in Direction::ALL {
+                assert_eq!(0, empty.shifted(direction).count_ones());
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::digits;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArithmeticError {
+    Overflow { operation: &'static str },
+    DivisionByZero,
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticError::Overflow { operation } => write!(f, "overflow in {}", operation),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+// The primitive integers, so solvers can be written once and run at whatever width they need
+pub trait CheckedInt: Copy + Ord + Debug + Display + FromStr {
+    const ZERO: Self;
+    const ONE: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exponent: u32) -> Option<Self>;
+}
+
+macro_rules! checked_int {
+    ($($t:ty),+) => {$(
+        impl CheckedInt for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn checked_rem(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem(self, rhs)
+            }
+
+            fn checked_pow(self, exponent: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exponent)
+            }
+        }
+    )+};
+}
+
+checked_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// A value that remembers the first overflow in a chain of operations, like NaN does for floats,
+// so a whole expression can be written with ordinary operators and checked once at the end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checked<T>(Result<T, ArithmeticError>);
+
+impl<T: CheckedInt> Checked<T> {
+    pub fn new(value: T) -> Self {
+        Checked(Ok(value))
+    }
+
+    pub fn get(self) -> Result<T, ArithmeticError> {
+        self.0
+    }
+
+    fn apply(self, rhs: Checked<T>, operation: &'static str, f: impl FnOnce(T, T) -> Option<T>) -> Self {
+        Checked(self.0.and_then(|a| {
+            let b = rhs.0?;
+            f(a, b).ok_or(ArithmeticError::Overflow { operation })
+        }))
+    }
+
+    fn divide(self, rhs: Checked<T>, operation: &'static str, f: impl FnOnce(T, T) -> Option<T>) -> Self {
+        match rhs.0 {
+            Ok(b) if b == T::ZERO => Checked(self.0.and(Err(ArithmeticError::DivisionByZero))),
+            _ => self.apply(rhs, operation, f),
+        }
+    }
+
+    pub fn pow(self, exponent: u32) -> Self {
+        Checked(self.0.and_then(|a| a.checked_pow(exponent).ok_or(ArithmeticError::Overflow { operation: "pow" })))
+    }
+
+    // Appends the decimal digits of rhs, so 12 concat 345 is 12345. rhs must not be negative.
+    pub fn concat(self, rhs: T) -> Self {
+        self.apply(Checked::new(rhs), "concat", digits::concat)
+    }
+
+    // Moves to another width, e.g. from i128 back down to usize
+    pub fn cast<U: CheckedInt + TryFrom<T>>(self) -> Checked<U> {
+        Checked(self.0.and_then(|a| U::try_from(a).map_err(|_| ArithmeticError::Overflow { operation: "cast" })))
+    }
+}
+
+impl<T: CheckedInt> From<T> for Checked<T> {
+    fn from(value: T) -> Self {
+        Checked::new(value)
+    }
+}
+
+macro_rules! checked_operator {
+    ($trait:ident, $method:ident, $apply:ident, $name:expr, $op:path) => {
+        impl<T: CheckedInt> $trait for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, rhs: Checked<T>) -> Checked<T> {
+                self.$apply(rhs, $name, $op)
+            }
+        }
+
+        impl<T: CheckedInt> $trait<T> for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, rhs: T) -> Checked<T> {
+                self.$apply(Checked::new(rhs), $name, $op)
+            }
+        }
+    };
+}
+
+checked_operator!(Add, add, apply, "addition", T::checked_add);
+checked_operator!(Sub, sub, apply, "subtraction", T::checked_sub);
+checked_operator!(Mul, mul, apply, "multiplication", T::checked_mul);
+checked_operator!(Div, div, divide, "division", T::checked_div);
+checked_operator!(Rem, rem, divide, "remainder", T::checked_rem);
+
+#[cfg(test)]
+mod tests {
+    mod checked {
+        use crate::checked::{ArithmeticError, Checked};
+
+        #[test]
+        fn arithmetic() {
+            assert_eq!(Ok(14), (Checked::new(2u8) * 5 + 4).get());
+            assert_eq!(Ok(3), (Checked::new(17i32) / 5).get());
+            assert_eq!(Ok(-2), (Checked::new(-17i32) % 5).get());
+            assert_eq!(Ok(1024), Checked::new(2u16).pow(10).get());
+        }
+
+        #[test]
+        fn overflow_sticks() {
+            let value = Checked::new(200u8) + 100 - 250;
+            assert_eq!(Err(ArithmeticError::Overflow { operation: "addition" }), value.get());
+            assert_eq!("overflow in addition", value.get().unwrap_err().to_string());
+            assert_eq!(Err(ArithmeticError::DivisionByZero), (Checked::new(1u32) / 0).get());
+            assert_eq!(Err(ArithmeticError::Overflow { operation: "subtraction" }), (Checked::new(0usize) - 1).get());
+        }
+
+        #[test]
+        fn concat() {
+            assert_eq!(Ok(12345), Checked::new(12u32).concat(345).get());
+            assert_eq!(Ok(100), Checked::new(10u32).concat(0).get());
+            assert_eq!(Ok(1010), Checked::new(10u32).concat(10).get());
+            assert!(Checked::new(u64::MAX / 10).concat(99).get().is_err());
+            assert_eq!(Ok(184467440737095516199), Checked::new(u64::MAX as u128 / 10).concat(99).get());
+        }
+
+        #[test]
+        fn cast() {
+            assert_eq!(Ok(300usize), Checked::new(300i128).cast::<usize>().get());
+            assert!(Checked::new(-1i128).cast::<usize>().get().is_err());
+            assert!(Checked::new(300i128).cast::<u8>().get().is_err());
+        }
+    }
+}
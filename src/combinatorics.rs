@@ -0,0 +1,207 @@
+// Enumerations that reuse one buffer for every candidate. A candidate is only borrowed until
+// the next call to advance, which rules out the Iterator trait, so they are walked with
+// `while let Some(candidate) = walker.advance() { ... }`.
+
+// Every sequence of `length` digits below `base`, counting up with the first digit turning
+// fastest, like an odometer read backwards
+#[derive(Clone, Debug)]
+pub struct Odometer {
+    base: usize,
+    digits: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl Odometer {
+    pub fn new(base: usize, length: usize) -> Self {
+        Odometer { base, digits: vec![0; length], started: false, done: base == 0 && length > 0 }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.digits);
+        }
+        for digit in self.digits.iter_mut() {
+            *digit += 1;
+            if *digit < self.base {
+                return Some(&self.digits);
+            }
+            *digit = 0;
+        }
+        self.done = true;
+        None
+    }
+}
+
+// The Cartesian power of an alphabet, in odometer order
+#[derive(Clone, Debug)]
+pub struct Product<'a, T> {
+    alphabet: &'a [T],
+    odometer: Odometer,
+    current: Vec<T>,
+}
+
+impl<'a, T: Clone> Product<'a, T> {
+    pub fn new(alphabet: &'a [T], length: usize) -> Self {
+        let current = alphabet.first().map(|first| vec![first.clone(); length]).unwrap_or_default();
+        Product { alphabet, odometer: Odometer::new(alphabet.len(), length), current }
+    }
+
+    pub fn advance(&mut self) -> Option<&[T]> {
+        let digits = self.odometer.advance()?;
+        // only the digits that rolled over have changed, but copying them all is just as cheap
+        for (value, &digit) in self.current.iter_mut().zip(digits) {
+            *value = self.alphabet[digit].clone();
+        }
+        Some(&self.current)
+    }
+}
+
+// The k-element subsets of 0..n as increasing index lists, in lexicographic order
+#[derive(Clone, Debug)]
+pub struct Combinations {
+    n: usize,
+    indices: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl Combinations {
+    pub fn new(n: usize, k: usize) -> Self {
+        Combinations { n, indices: (0..k).collect(), started: false, done: k > n }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.indices);
+        }
+        let k = self.indices.len();
+        // the rightmost index that can still move right
+        let Some(i) = (0..k).rev().find(|&i| self.indices[i] < self.n - k + i) else {
+            self.done = true;
+            return None;
+        };
+        self.indices[i] += 1;
+        for j in i + 1..k {
+            self.indices[j] = self.indices[j - 1] + 1;
+        }
+        Some(&self.indices)
+    }
+}
+
+// The orderings of 0..n in lexicographic order
+#[derive(Clone, Debug)]
+pub struct Permutations {
+    order: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl Permutations {
+    pub fn new(n: usize) -> Self {
+        Permutations { order: (0..n).collect(), started: false, done: false }
+    }
+
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            return Some(&self.order);
+        }
+        let order = &mut self.order;
+        let Some(i) = (1..order.len()).rev().find(|&i| order[i - 1] < order[i]) else {
+            self.done = true;
+            return None;
+        };
+        let j = (i..order.len()).rev().find(|&j| order[j] > order[i - 1]).unwrap();
+        order.swap(i - 1, j);
+        order[i..].reverse();
+        Some(&self.order)
+    }
+}
+
+// Each unordered pair of distinct positions once, as (items[i], items[j]) with i < j
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items.iter().enumerate().flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (a, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    mod walkers {
+        use crate::combinatorics::{Combinations, Odometer, Permutations, Product};
+
+        fn collect(advance: impl FnMut() -> Option<Vec<usize>>) -> Vec<Vec<usize>> {
+            std::iter::from_fn(advance).collect()
+        }
+
+        #[test]
+        fn odometer() {
+            let mut odometer = Odometer::new(3, 2);
+            let all = collect(|| odometer.advance().map(|d| d.to_vec()));
+            assert_eq!(9, all.len());
+            assert_eq!(vec![vec![0, 0], vec![1, 0], vec![2, 0], vec![0, 1]], all[..4]);
+            assert_eq!(None, odometer.advance());
+            let mut empty = Odometer::new(3, 0);
+            assert_eq!(Some(&[][..]), empty.advance());
+            assert_eq!(None, empty.advance());
+            assert_eq!(None, Odometer::new(0, 2).advance());
+        }
+
+        #[test]
+        fn product() {
+            let mut words = Product::new(&['a', 'b'], 3);
+            let mut all = Vec::new();
+            while let Some(word) = words.advance() {
+                all.push(word.iter().collect::<String>());
+            }
+            assert_eq!(vec!["aaa", "baa", "aba", "bba", "aab", "bab", "abb", "bbb"], all);
+        }
+
+        #[test]
+        fn combinations() {
+            let mut choices = Combinations::new(4, 2);
+            let all = collect(|| choices.advance().map(|c| c.to_vec()));
+            assert_eq!(vec![vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3], vec![2, 3]], all);
+            assert_eq!(None, Combinations::new(2, 3).advance());
+            let mut none = Combinations::new(3, 0);
+            assert_eq!(Some(&[][..]), none.advance());
+            assert_eq!(None, none.advance());
+        }
+
+        #[test]
+        fn permutations() {
+            let mut orders = Permutations::new(3);
+            let all = collect(|| orders.advance().map(|p| p.to_vec()));
+            assert_eq!(6, all.len());
+            assert_eq!(vec![0, 2, 1], all[1]);
+            assert_eq!(vec![2, 1, 0], all[5]);
+            let mut count = 0;
+            let mut orders = Permutations::new(6);
+            while orders.advance().is_some() {
+                count += 1;
+            }
+            assert_eq!(720, count);
+        }
+    }
+
+    mod pairs {
+        use crate::combinatorics::pairs;
+
+        #[test]
+        fn unordered() {
+            let all = pairs(&[1, 2, 3]).map(|(&a, &b)| (a, b)).collect::<Vec<_>>();
+            assert_eq!(vec![(1, 2), (1, 3), (2, 3)], all);
+            assert_eq!(0, pairs(&[1]).count());
+        }
+    }
+}
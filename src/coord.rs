@@ -0,0 +1,322 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use crate::grid::Cell;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Vector {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn from_cell(cell: Cell) -> Self {
+        Point { x: cell.1 as isize, y: cell.0 as isize }
+    }
+
+    pub fn to_cell(self) -> Option<Cell> {
+        if self.x < 0 || self.y < 0 {
+            None
+        } else {
+            Some((self.y as usize, self.x as usize))
+        }
+    }
+
+    pub fn to_cell_within(self, height: usize, width: usize) -> Option<Cell> {
+        self.to_cell().filter(|&(r, c)| r < height && c < width)
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.vector()
+    }
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Vector::new(x, y)))
+            .filter(|&v| v != Vector::ZERO)
+            .map(move |v| self + v)
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        (other - self).manhattan()
+    }
+
+    pub fn chebyshev(self, other: Point) -> usize {
+        (other - self).chebyshev()
+    }
+}
+
+impl Vector {
+    pub const ZERO: Vector = Vector { x: 0, y: 0 };
+
+    pub fn new(x: isize, y: isize) -> Self {
+        Vector { x, y }
+    }
+
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    pub fn chebyshev(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn parse(c: char) -> Option<Self> {
+        match c {
+            '^' | 'U' | 'N' => Some(Direction::North),
+            '>' | 'R' | 'E' => Some(Direction::East),
+            'v' | 'D' | 'S' => Some(Direction::South),
+            '<' | 'L' | 'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub fn vector(self) -> Vector {
+        match self {
+            Direction::North => Vector::new(0, -1),
+            Direction::East => Vector::new(1, 0),
+            Direction::South => Vector::new(0, 1),
+            Direction::West => Vector::new(-1, 0),
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+}
+
+impl Turn {
+    pub fn parse(c: char) -> Option<Self> {
+        match c {
+            'L' => Some(Turn::Left),
+            'R' => Some(Turn::Right),
+            _ => None,
+        }
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Vector) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Vector {
+        Vector::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: isize) -> Vector {
+        Vector::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl From<Direction> for Vector {
+    fn from(direction: Direction) -> Self {
+        direction.vector()
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod point {
+        use crate::coord::{Direction, Point, Vector};
+
+        #[test]
+        fn arithmetic() {
+            let p = Point::new(3, 4);
+            assert_eq!(Point::new(5, 3), p + Vector::new(2, -1));
+            assert_eq!(Vector::new(3, 4), p - Point::ORIGIN);
+            assert_eq!(Point::new(3, 3), p.step(Direction::North));
+            assert_eq!(Vector::new(-4, 2), -Vector::new(2, -1) * 2);
+        }
+
+        #[test]
+        fn distances() {
+            let a = Point::new(1, 6);
+            let b = Point::new(5, 11);
+            assert_eq!(9, a.manhattan(b));
+            assert_eq!(5, a.chebyshev(b));
+        }
+
+        #[test]
+        fn checked_cells() {
+            assert_eq!(Some((4, 3)), Point::new(3, 4).to_cell());
+            assert_eq!(None, Point::new(-1, 4).to_cell());
+            assert_eq!(None, Point::new(3, 4).to_cell_within(4, 10));
+            assert_eq!(Point::new(3, 4), Point::from_cell((4, 3)));
+        }
+
+        #[test]
+        fn neighbours() {
+            assert_eq!(4, Point::ORIGIN.neighbours4().count());
+            assert_eq!(8, Point::ORIGIN.neighbours8().count());
+            assert!(Point::ORIGIN.neighbours8().all(|n| n.chebyshev(Point::ORIGIN) == 1));
+        }
+    }
+
+    mod direction {
+        use crate::coord::Direction::*;
+        use crate::coord::{Direction, Turn};
+
+        #[test]
+        fn turns() {
+            assert_eq!(East, North.turn_right());
+            assert_eq!(West, North.turn_left());
+            assert_eq!(South, North.reverse());
+            assert_eq!(North, West.turn(Turn::Right));
+            for d in Direction::ALL {
+                assert_eq!(d, d.turn_left().turn_right());
+                assert_eq!(d.reverse(), d.turn_right().turn_right());
+            }
+        }
+
+        #[test]
+        fn parse() {
+            for (c, expected) in [('^', North), ('v', South), ('<', West), ('>', East),
+                                  ('U', North), ('D', South), ('L', West), ('R', East),
+                                  ('N', North), ('S', South), ('W', West), ('E', East)] {
+                assert_eq!(Some(expected), Direction::parse(c));
+            }
+            assert_eq!(None, Direction::parse('x'));
+            assert_eq!(Some(Turn::Left), Turn::parse('L'));
+        }
+    }
+}
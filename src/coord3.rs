@@ -0,0 +1,441 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use crate::parse::{field, scan_fields, ParseError};
+
+// The 3D counterparts of coord::Point and coord::Vector. z is "up" wherever height matters.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Vec3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+// An axis-aligned box; both corners are inside it
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cuboid {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn neighbours6(self) -> impl Iterator<Item = Point3> {
+        Vec3::UNITS.into_iter().flat_map(move |v| [self + v, self - v])
+    }
+
+    pub fn neighbours26(self) -> impl Iterator<Item = Point3> {
+        (-1..=1)
+            .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| Vec3::new(x, y, z))))
+            .filter(|&v| v != Vec3::ZERO)
+            .map(move |v| self + v)
+    }
+
+    pub fn manhattan(self, other: Point3) -> usize {
+        (other - self).manhattan()
+    }
+
+    pub fn chebyshev(self, other: Point3) -> usize {
+        (other - self).chebyshev()
+    }
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3 { x: 0, y: 0, z: 0 };
+    pub const UNITS: [Vec3; 3] = [Vec3 { x: 1, y: 0, z: 0 }, Vec3 { x: 0, y: 1, z: 0 }, Vec3 { x: 0, y: 0, z: 1 }];
+
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    pub fn chebyshev(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs()).max(self.z.unsigned_abs())
+    }
+
+    pub fn dot(self, other: Vec3) -> isize {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl Cuboid {
+    // The corners may be given in any order
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Cuboid {
+            min: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min + Vec3::new(1, 1, 1)
+    }
+
+    pub fn volume(&self) -> usize {
+        let size = self.size();
+        (size.x * size.y * size.z) as usize
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn contains_cuboid(&self, other: &Cuboid) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersects(&self, other: &Cuboid) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = Point3::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z));
+        let max = Point3::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z));
+        (min.x <= max.x && min.y <= max.y && min.z <= max.z).then_some(Cuboid { min, max })
+    }
+
+    // The smallest box holding both
+    pub fn union(&self, other: &Cuboid) -> Cuboid {
+        Cuboid::new(
+            Point3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            Point3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+        )
+    }
+
+    pub fn translated(&self, v: Vec3) -> Cuboid {
+        Cuboid { min: self.min + v, max: self.max + v }
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point3> + '_ {
+        (self.min.z..=self.max.z).flat_map(move |z| {
+            (self.min.y..=self.max.y).flat_map(move |y| (self.min.x..=self.max.x).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+}
+
+// Occupied voxels with no fixed bounds. Each (x, y) column keeps its occupied heights sorted,
+// so the height map can be read without scanning.
+#[derive(Clone, Debug, Default)]
+pub struct VoxelSet {
+    voxels: HashSet<Point3>,
+    columns: HashMap<(isize, isize), BTreeSet<isize>>,
+}
+
+impl VoxelSet {
+    pub fn new() -> Self {
+        VoxelSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        self.voxels.contains(&p)
+    }
+
+    // Returns false when the voxel was already occupied
+    pub fn insert(&mut self, p: Point3) -> bool {
+        if !self.voxels.insert(p) {
+            return false;
+        }
+        self.columns.entry((p.x, p.y)).or_default().insert(p.z);
+        true
+    }
+
+    pub fn remove(&mut self, p: Point3) -> bool {
+        if !self.voxels.remove(&p) {
+            return false;
+        }
+        let column = self.columns.get_mut(&(p.x, p.y)).unwrap();
+        column.remove(&p.z);
+        if column.is_empty() {
+            self.columns.remove(&(p.x, p.y));
+        }
+        true
+    }
+
+    pub fn insert_cuboid(&mut self, cuboid: &Cuboid) {
+        for p in cuboid.points() {
+            self.insert(p);
+        }
+    }
+
+    pub fn remove_cuboid(&mut self, cuboid: &Cuboid) {
+        for p in cuboid.points() {
+            self.remove(p);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point3> + '_ {
+        self.voxels.iter().copied()
+    }
+
+    pub fn bounds(&self) -> Option<Cuboid> {
+        self.voxels.iter().map(|&p| Cuboid::new(p, p)).reduce(|a, b| a.union(&b))
+    }
+
+    // The highest occupied z in the column, if any
+    pub fn height(&self, x: isize, y: isize) -> Option<isize> {
+        self.columns.get(&(x, y)).and_then(|column| column.last().copied())
+    }
+
+    // The highest occupied z strictly below the given one, e.g. where a falling voxel lands
+    pub fn height_below(&self, x: isize, y: isize, z: isize) -> Option<isize> {
+        self.columns.get(&(x, y)).and_then(|column| column.range(..z).next_back().copied())
+    }
+
+    pub fn height_map(&self) -> HashMap<(isize, isize), isize> {
+        self.columns.iter().map(|(&xy, column)| (xy, *column.last().unwrap())).collect()
+    }
+
+    // Faces not shared with another occupied voxel
+    pub fn surface_area(&self) -> usize {
+        self.voxels.iter()
+            .map(|p| p.neighbours6().filter(|n| !self.voxels.contains(n)).count())
+            .sum()
+    }
+}
+
+impl FromIterator<Point3> for VoxelSet {
+    fn from_iter<I: IntoIterator<Item = Point3>>(iter: I) -> Self {
+        let mut voxels = VoxelSet::new();
+        for p in iter {
+            voxels.insert(p);
+        }
+        voxels
+    }
+}
+
+// "x, y, z" with any spacing around the commas; column is where the text starts in its line
+fn triple((text, column): (&str, usize)) -> Result<[isize; 3], ParseError> {
+    let mut values = [0; 3];
+    let mut parts = text.split(',');
+    let mut offset = 0;
+    for value in values.iter_mut() {
+        let part = parts.next().ok_or_else(|| ParseError::new(column + offset, "expected three coordinates"))?;
+        let trimmed = part.trim_start();
+        *value = field((trimmed.trim_end(), column + offset + part.len() - trimmed.len()))?;
+        offset += part.len() + 1;
+    }
+    if parts.next().is_some() {
+        return Err(ParseError::new(column + offset - 1, "expected three coordinates"));
+    }
+    Ok(values)
+}
+
+// A brick such as "1,0,1~1,2,1"
+pub fn parse_cuboid(line: &str) -> Result<Cuboid, ParseError> {
+    let mut fields = scan_fields("{}~{}", line, 2)?.into_iter();
+    let [ax, ay, az] = triple(fields.next().unwrap())?;
+    let [bx, by, bz] = triple(fields.next().unwrap())?;
+    Ok(Cuboid::new(Point3::new(ax, ay, az), Point3::new(bx, by, bz)))
+}
+
+// A hailstone such as "19, 13, 30 @ -2,  1, -2"
+pub fn parse_trajectory(line: &str) -> Result<(Point3, Vec3), ParseError> {
+    let mut fields = scan_fields("{} @ {}", line, 2)?.into_iter();
+    let [x, y, z] = triple(fields.next().unwrap())?;
+    let [dx, dy, dz] = triple(fields.next().unwrap())?;
+    Ok((Point3::new(x, y, z), Vec3::new(dx, dy, dz)))
+}
+
+impl Add<Vec3> for Point3 {
+    type Output = Point3;
+
+    fn add(self, rhs: Vec3) -> Point3 {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign<Vec3> for Point3 {
+    fn add_assign(&mut self, rhs: Vec3) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vec3> for Point3 {
+    type Output = Point3;
+
+    fn sub(self, rhs: Vec3) -> Point3 {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl SubAssign<Vec3> for Point3 {
+    fn sub_assign(&mut self, rhs: Vec3) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Point3) -> Vec3 {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<isize> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: isize) -> Vec3 {
+        Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
+
+impl Display for Cuboid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}~{},{},{}", self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod point {
+        use crate::coord3::{Point3, Vec3};
+
+        #[test]
+        fn arithmetic() {
+            let p = Point3::new(1, 2, 3);
+            assert_eq!(Point3::new(3, 1, 3), p + Vec3::new(2, -1, 0));
+            assert_eq!(Vec3::new(1, 2, 3), p - Point3::ORIGIN);
+            assert_eq!(Vec3::new(-2, -4, -6), -(p - Point3::ORIGIN) * 2);
+            assert_eq!(Vec3::UNITS[2], Vec3::UNITS[0].cross(Vec3::UNITS[1]));
+            assert_eq!(14, Vec3::new(1, 2, 3).dot(Vec3::new(1, 2, 3)));
+        }
+
+        #[test]
+        fn neighbours() {
+            assert_eq!(6, Point3::ORIGIN.neighbours6().count());
+            assert!(Point3::ORIGIN.neighbours6().all(|n| n.manhattan(Point3::ORIGIN) == 1));
+            assert_eq!(26, Point3::ORIGIN.neighbours26().count());
+            assert!(Point3::ORIGIN.neighbours26().all(|n| n.chebyshev(Point3::ORIGIN) == 1));
+        }
+    }
+
+    mod cuboid {
+        use crate::coord3::{Cuboid, Point3, Vec3};
+
+        #[test]
+        fn overlap() {
+            let a = Cuboid::new(Point3::new(2, 2, 2), Point3::new(0, 0, 0));
+            let b = Cuboid::new(Point3::new(1, 1, 1), Point3::new(4, 1, 1));
+            assert_eq!(27, a.volume());
+            assert!(a.contains(Point3::new(2, 0, 1)));
+            assert!(!a.contains(Point3::new(3, 0, 1)));
+            assert_eq!(Some(Cuboid::new(Point3::new(1, 1, 1), Point3::new(2, 1, 1))), a.intersection(&b));
+            assert!(!a.intersects(&b.translated(Vec3::new(0, 0, 2))));
+            assert!(a.contains_cuboid(&Cuboid::new(Point3::new(1, 1, 1), Point3::new(2, 2, 2))));
+            assert!(!a.contains_cuboid(&b));
+            assert_eq!(27, a.points().count());
+        }
+    }
+
+    mod voxels {
+        use crate::coord3::{parse_cuboid, Point3, VoxelSet};
+
+        #[test]
+        fn height_map() {
+            let mut voxels = VoxelSet::new();
+            voxels.insert_cuboid(&parse_cuboid("1,0,1~1,2,1").unwrap());
+            voxels.insert_cuboid(&parse_cuboid("1,1,8~1,1,9").unwrap());
+            assert_eq!(5, voxels.len());
+            assert_eq!(Some(9), voxels.height(1, 1));
+            assert_eq!(Some(1), voxels.height_below(1, 1, 8));
+            assert_eq!(None, voxels.height(0, 0));
+            assert_eq!(3, voxels.height_map().len());
+
+            voxels.remove(Point3::new(1, 0, 1));
+            assert_eq!(None, voxels.height(1, 0));
+            assert_eq!(2, voxels.height_map().len());
+        }
+
+        #[test]
+        fn surface_area() {
+            let voxels: VoxelSet = [Point3::new(1, 1, 1), Point3::new(2, 1, 1)].into_iter().collect();
+            assert_eq!(10, voxels.surface_area());
+            assert_eq!(Some(2), voxels.bounds().map(|b| b.volume()));
+        }
+    }
+
+    mod parse {
+        use crate::coord3::{parse_cuboid, parse_trajectory, Point3, Vec3};
+
+        #[test]
+        fn cuboid() {
+            let brick = parse_cuboid("2,2,2~2,2,2").unwrap();
+            assert_eq!(1, brick.volume());
+            assert_eq!("0,0,4~0,2,4", parse_cuboid("0,2,4~0,0,4").unwrap().to_string());
+            assert_eq!(9, parse_cuboid("1,0,1~1,x,1").unwrap_err().column);
+        }
+
+        #[test]
+        fn trajectory() {
+            assert_eq!(
+                Ok((Point3::new(19, 13, 30), Vec3::new(-2, 1, -2))),
+                parse_trajectory("19, 13, 30 @ -2,  1, -2")
+            );
+            assert!(parse_trajectory("19, 13 @ -2, 1, -2").is_err());
+        }
+    }
+}
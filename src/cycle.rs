@@ -0,0 +1,128 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub initial: S,
+    pub start: S,
+    pub prefix: usize,
+    pub length: usize,
+}
+
+// Brent's algorithm, so only two states are held at a time no matter how long the cycle is
+pub fn find_cycle<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { initial, start: tortoise, prefix, length }
+}
+
+impl<S: Clone + Eq> Cycle<S> {
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+
+    pub fn state_at(&self, n: usize, mut step: impl FnMut(&S) -> S) -> S {
+        let (mut state, remaining) = if n < self.prefix {
+            (self.initial.clone(), n)
+        } else {
+            (self.start.clone(), (n - self.prefix) % self.length)
+        };
+        for _ in 0..remaining {
+            state = step(&state);
+        }
+        state
+    }
+
+    pub fn matches(
+        &self,
+        mut step: impl FnMut(&S) -> S,
+        mut predicate: impl FnMut(&S) -> bool,
+    ) -> Vec<usize> {
+        let mut positions = Vec::new();
+        let mut state = self.start.clone();
+        for offset in 0..self.length {
+            if predicate(&state) {
+                positions.push(self.prefix + offset);
+            }
+            state = step(&state);
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_cycle {
+        use crate::cycle::find_cycle;
+
+        #[test]
+        fn rho_shape() {
+            // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3
+            let step = |&n: &usize| if n == 6 { 3 } else { n + 1 };
+            let cycle = find_cycle(0, step);
+            assert_eq!(3, cycle.prefix);
+            assert_eq!(4, cycle.length);
+            assert_eq!(3, cycle.start);
+        }
+
+        #[test]
+        fn pure_cycle() {
+            let cycle = find_cycle(2u64, |&n| (n * 3) % 7);
+            assert_eq!(0, cycle.prefix);
+            assert_eq!(6, cycle.length);
+        }
+
+        #[test]
+        fn fixed_point() {
+            let cycle = find_cycle(10, |&n: &i32| if n > 0 { n - 1 } else { 0 });
+            assert_eq!(10, cycle.prefix);
+            assert_eq!(1, cycle.length);
+        }
+    }
+
+    mod queries {
+        use crate::cycle::find_cycle;
+
+        #[test]
+        fn far_future() {
+            let step = |&n: &usize| if n == 6 { 3 } else { n + 1 };
+            let cycle = find_cycle(0, step);
+            assert_eq!(2, cycle.state_at(2, step));
+            assert_eq!(4, cycle.equivalent_step(1_000_000_000));
+            assert_eq!(4, cycle.state_at(1_000_000_000, step));
+            assert_eq!(5, cycle.state_at(1_000_000_001, step));
+        }
+
+        #[test]
+        fn matching_positions() {
+            let step = |&n: &usize| if n == 6 { 3 } else { n + 1 };
+            let cycle = find_cycle(0, step);
+            assert_eq!(vec![4, 6], cycle.matches(step, |&n| n % 2 == 0));
+        }
+    }
+}
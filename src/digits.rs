@@ -0,0 +1,125 @@
+use crate::checked::CheckedInt;
+
+// Decimal digits of non-negative integers, by arithmetic rather than through strings
+
+fn div10<T: CheckedInt>(n: T) -> T {
+    n.checked_div(T::TEN).unwrap()
+}
+
+// 0 has one digit
+pub fn count<T: CheckedInt>(n: T) -> u32 {
+    let mut rest = n;
+    let mut digits = 1;
+    while rest >= T::TEN {
+        rest = div10(rest);
+        digits += 1;
+    }
+    digits
+}
+
+// The top digits and the last `bottom` digits, so 123456 split at 2 is (1234, 56)
+pub fn split<T: CheckedInt>(n: T, bottom: u32) -> (T, T) {
+    match T::TEN.checked_pow(bottom) {
+        Some(shift) => (n.checked_div(shift).unwrap(), n.checked_rem(shift).unwrap()),
+        None => (T::ZERO, n),
+    }
+}
+
+// 1234 is (12, 34) and 1000 is (10, 0); None for an odd number of digits
+pub fn halves<T: CheckedInt>(n: T) -> Option<(T, T)> {
+    let digits = count(n);
+    digits.is_multiple_of(2).then(|| split(n, digits / 2))
+}
+
+// 12 and 345 make 12345; None on overflow
+pub fn concat<T: CheckedInt>(top: T, bottom: T) -> Option<T> {
+    top.checked_mul(T::TEN.checked_pow(count(bottom))?)?.checked_add(bottom)
+}
+
+// What is left of n after removing the digits of suffix from its end, so 12345 without 45 is
+// 123. This undoes concat when working an equation backwards from its result.
+pub fn strip_suffix<T: CheckedInt>(n: T, suffix: T) -> Option<T> {
+    let (top, bottom) = split(n, count(suffix));
+    (bottom == suffix).then_some(top)
+}
+
+pub fn ends_with<T: CheckedInt>(n: T, suffix: T) -> bool {
+    strip_suffix(n, suffix).is_some()
+}
+
+// Most significant digit first
+pub fn digits<T: CheckedInt>(n: T) -> Digits<T> {
+    let unit = T::TEN.checked_pow(count(n) - 1).unwrap();
+    Digits { rest: n, unit: Some(unit) }
+}
+
+#[derive(Clone, Debug)]
+pub struct Digits<T> {
+    rest: T,
+    unit: Option<T>,
+}
+
+impl<T: CheckedInt> Iterator for Digits<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let unit = self.unit?;
+        let digit = self.rest.checked_div(unit).unwrap();
+        self.rest = self.rest.checked_rem(unit).unwrap();
+        self.unit = (unit > T::ONE).then(|| div10(unit));
+        Some(digit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod digits {
+        use crate::digits::{concat, count, digits, ends_with, halves, split, strip_suffix};
+
+        #[test]
+        fn counting() {
+            assert_eq!(1, count(0u64));
+            assert_eq!(1, count(9u64));
+            assert_eq!(2, count(10u64));
+            assert_eq!(20, count(u64::MAX));
+            assert_eq!(3, count(255u8));
+        }
+
+        #[test]
+        fn splitting() {
+            assert_eq!((1234, 56), split(123456u64, 2));
+            assert_eq!((0, 42), split(42u8, 5));
+            assert_eq!(Some((10, 0)), halves(1000u64));
+            assert_eq!(Some((2, 3)), halves(23usize));
+            assert_eq!(None, halves(123usize));
+            assert_eq!(None, halves(0usize));
+        }
+
+        #[test]
+        fn concatenation() {
+            assert_eq!(Some(12345), concat(12u32, 345));
+            assert_eq!(Some(100), concat(10u32, 0));
+            assert_eq!(None, concat(u64::MAX / 10, 99));
+            assert_eq!(Some(250), concat(25u8, 0));
+            assert_eq!(None, concat(26u8, 0));
+        }
+
+        #[test]
+        fn suffixes() {
+            assert_eq!(Some(123), strip_suffix(12345u64, 45));
+            assert_eq!(Some(0), strip_suffix(45u64, 45));
+            assert_eq!(Some(10), strip_suffix(100u64, 0));
+            assert_eq!(None, strip_suffix(5u64, 45));
+            assert!(ends_with(7290usize, 90));
+            assert!(!ends_with(7290usize, 9));
+        }
+
+        #[test]
+        fn iterates() {
+            assert_eq!(vec![1, 0, 2, 4], digits(1024u32).collect::<Vec<_>>());
+            assert_eq!(vec![0], digits(0u32).collect::<Vec<_>>());
+            assert_eq!(20, digits(u64::MAX).count());
+            assert_eq!(vec![2, 5, 5], digits(255u8).collect::<Vec<_>>());
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+use crate::grid::{Cell, Grid};
+use crate::union_find::UnionFind;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub id: usize,
+    pub cells: Vec<Cell>,
+    pub top_left: Cell,
+    pub bottom_right: Cell,
+    pub boundary: Vec<Cell>,
+}
+
+impl Component {
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn bounding_box(&self) -> (Cell, Cell) {
+        (self.top_left, self.bottom_right)
+    }
+}
+
+pub struct Labels {
+    pub labels: Grid<usize>,
+    pub components: Vec<Component>,
+}
+
+impl Labels {
+    pub fn component_at(&self, cell: Cell) -> &Component {
+        &self.components[self.labels[cell]]
+    }
+}
+
+pub fn flood_fill<T>(grid: &Grid<T>, start: Cell, connected: impl Fn(&T, &T) -> bool) -> Vec<Cell> {
+    let mut seen = grid.map(|_| false);
+    fill(grid, start, &connected, &mut seen)
+}
+
+// Components are numbered in reading order of their first cell, and list their cells in
+// reading order. connected must be symmetric, since each pair of neighbours is only asked once.
+pub fn label<T>(grid: &Grid<T>, connected: impl Fn(&T, &T) -> bool) -> Labels {
+    let index = |(r, c): Cell| r * grid.width() + c;
+    let mut sets = UnionFind::new(grid.height() * grid.width());
+    for (cell, value) in grid.cells() {
+        for next in [(cell.0 + 1, cell.1), (cell.0, cell.1 + 1)] {
+            if grid.get(next).is_some_and(|other| connected(value, other)) {
+                sets.union(index(cell), index(next));
+            }
+        }
+    }
+
+    let mut labels = grid.map(|_| usize::MAX);
+    let mut component_of_root = vec![usize::MAX; sets.len()];
+    let mut components: Vec<Component> = Vec::new();
+    for (cell, _) in grid.cells() {
+        let root = sets.find(index(cell));
+        if component_of_root[root] == usize::MAX {
+            component_of_root[root] = components.len();
+            components.push(Component {
+                id: components.len(),
+                cells: Vec::new(),
+                top_left: cell,
+                bottom_right: cell,
+                boundary: Vec::new(),
+            });
+        }
+        // on the boundary when a neighbour is off the grid or in another component
+        let on_boundary = grid.neighbours4(cell).count() < 4
+            || grid.neighbours4(cell).any(|next| sets.find(index(next)) != root);
+        let component = &mut components[component_of_root[root]];
+        component.cells.push(cell);
+        if on_boundary {
+            component.boundary.push(cell);
+        }
+        component.top_left = (component.top_left.0.min(cell.0), component.top_left.1.min(cell.1));
+        component.bottom_right = (component.bottom_right.0.max(cell.0), component.bottom_right.1.max(cell.1));
+        labels[cell] = component.id;
+    }
+
+    Labels { labels, components }
+}
+
+fn fill<T>(
+    grid: &Grid<T>,
+    start: Cell,
+    connected: &impl Fn(&T, &T) -> bool,
+    seen: &mut Grid<bool>,
+) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut pending = VecDeque::from([start]);
+    seen[start] = true;
+    while let Some(cell) = pending.pop_front() {
+        cells.push(cell);
+        for next in grid.neighbours4(cell) {
+            if !seen[next] && connected(&grid[cell], &grid[next]) {
+                seen[next] = true;
+                pending.push_back(next);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    mod label {
+        use crate::flood::label;
+        use crate::grid::Grid;
+
+        const GARDEN: &str = "\
+AAAA
+BBCD
+BBCC
+EEEC";
+
+        #[test]
+        fn components() {
+            let garden = Grid::parse(GARDEN, |c| c);
+            let labels = label(&garden, |a, b| a == b);
+            assert_eq!(5, labels.components.len());
+            let sizes: Vec<usize> = labels.components.iter().map(|c| c.size()).collect();
+            assert_eq!(vec![4, 4, 4, 1, 3], sizes);
+            assert_eq!(labels.labels[(2, 3)], labels.labels[(1, 2)]);
+        }
+
+        #[test]
+        fn bounding_box() {
+            let garden = Grid::parse(GARDEN, |c| c);
+            let labels = label(&garden, |a, b| a == b);
+            assert_eq!(((1, 2), (3, 3)), labels.component_at((3, 3)).bounding_box());
+        }
+
+        #[test]
+        fn boundary() {
+            let grid = Grid::parse("OOOOO\nOOOOO\nOOOOO\nOOOOO", |c| c);
+            let labels = label(&grid, |a, b| a == b);
+            assert_eq!(1, labels.components.len());
+            assert_eq!(14, labels.components[0].boundary.len());
+            assert!(!labels.components[0].boundary.contains(&(1, 1)));
+            let garden = Grid::parse("AAAA\nBBCD\nBBCC\nEEEC", |c| c);
+            assert_eq!(vec![(1, 3)], label(&garden, |a, b| a == b).component_at((1, 3)).boundary);
+        }
+    }
+
+    mod flood_fill {
+        use crate::flood::flood_fill;
+        use crate::grid::Grid;
+
+        #[test]
+        fn walls_stop_fill() {
+            let grid = Grid::parse("..#..\n..#..\n#####", |c| c);
+            let filled = flood_fill(&grid, (0, 0), |a, b| *a == '.' && *b == '.');
+            assert_eq!(4, filled.len());
+            assert!(!filled.contains(&(0, 3)));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::coord::Point;
+use crate::number_theory::gcd;
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices.iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+pub fn double_signed_area(vertices: &[Point]) -> isize {
+    edges(vertices)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum()
+}
+
+pub fn double_area(vertices: &[Point]) -> usize {
+    double_signed_area(vertices).unsigned_abs()
+}
+
+pub fn area(vertices: &[Point]) -> f64 {
+    double_area(vertices) as f64 / 2.0
+}
+
+pub fn boundary_length(vertices: &[Point]) -> usize {
+    edges(vertices)
+        .map(|(a, b)| {
+            let d = b - a;
+            gcd(d.x.unsigned_abs(), d.y.unsigned_abs())
+        })
+        .sum()
+}
+
+// Zero for degenerate polygons, such as a single segment, which enclose nothing
+pub fn interior_points(vertices: &[Point]) -> usize {
+    // Pick's theorem: A = I + B/2 - 1
+    let double_area = double_area(vertices) as isize;
+    if double_area == 0 {
+        return 0;
+    }
+    ((double_area + 2 - boundary_length(vertices) as isize) / 2).max(0) as usize
+}
+
+pub fn on_boundary(vertices: &[Point], p: Point) -> bool {
+    edges(vertices).any(|(a, b)| {
+        let ab = b - a;
+        let ap = p - a;
+        ab.x * ap.y == ab.y * ap.x
+            && p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x)
+            && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+    })
+}
+
+pub fn contains(vertices: &[Point], p: Point) -> bool {
+    if on_boundary(vertices, p) {
+        return false;
+    }
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if (a.y > p.y) != (b.y > p.y) {
+            // x-coordinate where the edge crosses the horizontal through p, compared without division
+            let lhs = (p.x - a.x) * (b.y - a.y);
+            let rhs = (b.x - a.x) * (p.y - a.y);
+            if (b.y > a.y && lhs < rhs) || (b.y < a.y && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    mod area {
+        use crate::coord::Point;
+        use crate::geometry::{area, boundary_length, double_signed_area, interior_points};
+
+        fn square(size: isize) -> Vec<Point> {
+            vec![Point::new(0, 0), Point::new(size, 0), Point::new(size, size), Point::new(0, size)]
+        }
+
+        #[test]
+        fn shoelace() {
+            assert_eq!(16.0, area(&square(4)));
+            let triangle = vec![Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+            assert_eq!(4.5, area(&triangle));
+        }
+
+        #[test]
+        fn orientation() {
+            let mut vertices = square(2);
+            assert_eq!(8, double_signed_area(&vertices));
+            vertices.reverse();
+            assert_eq!(-8, double_signed_area(&vertices));
+        }
+
+        #[test]
+        fn picks_theorem() {
+            assert_eq!(16, boundary_length(&square(4)));
+            assert_eq!(9, interior_points(&square(4)));
+            let triangle = vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 4)];
+            assert_eq!(12, boundary_length(&triangle));
+            assert_eq!(3, interior_points(&triangle));
+        }
+
+        #[test]
+        fn degenerate() {
+            assert_eq!(0, interior_points(&[]));
+            assert_eq!(0, interior_points(&[Point::new(0, 0), Point::new(5, 0)]));
+            assert_eq!(0, interior_points(&[Point::new(0, 0), Point::new(2, 2), Point::new(4, 4)]));
+        }
+    }
+
+    mod contains {
+        use crate::coord::Point;
+        use crate::geometry::{contains, on_boundary};
+
+        #[test]
+        fn concave() {
+            // U shape opening upwards
+            let u = vec![
+                Point::new(0, 0), Point::new(2, 0), Point::new(2, 6), Point::new(6, 6),
+                Point::new(6, 0), Point::new(8, 0), Point::new(8, 8), Point::new(0, 8),
+            ];
+            assert!(contains(&u, Point::new(1, 4)));
+            assert!(contains(&u, Point::new(4, 7)));
+            assert!(!contains(&u, Point::new(4, 2)));
+            assert!(on_boundary(&u, Point::new(4, 6)));
+            assert!(!contains(&u, Point::new(4, 6)));
+            assert!(!contains(&u, Point::new(9, 4)));
+        }
+
+        #[test]
+        fn square_interior() {
+            let square = vec![Point::new(0, 0), Point::new(4, 0), Point::new(4, 4), Point::new(0, 4)];
+            assert!(contains(&square, Point::new(2, 2)));
+            assert!(contains(&square, Point::new(1, 3)));
+            assert!(!contains(&square, Point::new(0, 2)));
+            assert!(!contains(&square, Point::new(-1, 2)));
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter, Write};
+use std::hash::Hash;
+use crate::interner::{Id, Interner};
+
+// Node ids are the ids of an interner, so nodes are numbered in the order they are first seen.
+// Each node keeps its outgoing edges in insertion order, so callers can give meaning to "first
+// successor", "second successor", ... An undirected edge is stored as a pair of directed ones.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Interner<N>,
+    successors: Vec<Vec<Id>>,
+    directed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub cycle: Vec<N>,
+}
+
+impl<N: Display> Display for CycleError<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle through ")?;
+        for (i, node) in self.cycle.iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", node)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn directed() -> Self {
+        Graph::directed_with(Interner::new())
+    }
+
+    // A graph over already interned nodes, so a parser can add edges by id
+    pub fn directed_with(nodes: Interner<N>) -> Self {
+        Graph { successors: vec![Vec::new(); nodes.len()], nodes, directed: true }
+    }
+
+    pub fn undirected_with(nodes: Interner<N>) -> Self {
+        Graph { directed: false, ..Graph::directed_with(nodes) }
+    }
+
+    pub fn undirected() -> Self {
+        Graph { directed: false, ..Graph::directed() }
+    }
+
+    pub fn directed_from(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Graph::directed();
+        graph.extend(edges);
+        graph
+    }
+
+    pub fn undirected_from(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Graph::undirected();
+        graph.extend(edges);
+        graph
+    }
+
+    pub fn extend(&mut self, edges: impl IntoIterator<Item = (N, N)>) {
+        for (from, to) in edges {
+            self.add_edge(from, to);
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn add_node(&mut self, node: N) -> Id {
+        let id = self.nodes.intern(node);
+        if id as usize == self.successors.len() {
+            self.successors.push(Vec::new());
+        }
+        id
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.add_edge_ids(from, to);
+    }
+
+    pub fn add_edge_ids(&mut self, from: Id, to: Id) {
+        self.successors[from as usize].push(to);
+        if !self.directed && from != to {
+            self.successors[to as usize].push(from);
+        }
+    }
+
+    pub fn extend_ids(&mut self, edges: impl IntoIterator<Item = (Id, Id)>) {
+        for (from, to) in edges {
+            self.add_edge_ids(from, to);
+        }
+    }
+
+    pub fn node_id<Q>(&self, node: &Q) -> Option<Id>
+    where
+        N: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.nodes.get(node)
+    }
+
+    pub fn contains<Q>(&self, node: &Q) -> bool
+    where
+        N: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.nodes.contains(node)
+    }
+
+    pub fn node(&self, id: Id) -> &N {
+        self.nodes.resolve(id)
+    }
+
+    // Indexed by id
+    pub fn nodes(&self) -> &[N] {
+        self.nodes.values()
+    }
+
+    pub fn interner(&self) -> &Interner<N> {
+        &self.nodes
+    }
+
+    pub fn successor_ids(&self, id: Id) -> &[Id] {
+        &self.successors[id as usize]
+    }
+
+    fn targets(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.successors[id].iter().map(|&to| to as usize)
+    }
+
+    pub fn successors<Q>(&self, node: &Q) -> impl Iterator<Item = &N>
+    where
+        N: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.node_id(node)
+            .map_or(&[][..], |id| self.successor_ids(id))
+            .iter()
+            .map(|&id| self.node(id))
+    }
+
+    // Each undirected edge is listed once
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        (0..self.len()).flat_map(move |from| {
+            self.targets(from)
+                .filter(move |&to| self.directed || from <= to)
+                .map(move |to| (&self.nodes()[from], &self.nodes()[to]))
+        })
+    }
+
+    fn reachable_ids(&self, starts: impl IntoIterator<Item = usize>) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut pending: VecDeque<usize> = starts.into_iter().collect();
+        for &start in &pending {
+            seen[start] = true;
+        }
+        while let Some(id) = pending.pop_front() {
+            for next in self.targets(id) {
+                if !seen[next] {
+                    seen[next] = true;
+                    pending.push_back(next);
+                }
+            }
+        }
+        seen
+    }
+
+    // Includes the start itself
+    pub fn reachable_from<Q>(&self, start: &Q) -> HashSet<N>
+    where
+        N: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(start) = self.node_id(start) else { return HashSet::new() };
+        self.reachable_ids([start as usize])
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, seen)| seen)
+            .map(|(id, _)| self.nodes()[id].clone())
+            .collect()
+    }
+
+    // Kahn's algorithm; ties are broken by the order nodes were added
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        assert!(self.directed, "Only a directed graph has a topological order");
+        let mut in_degree = vec![0; self.len()];
+        for targets in &self.successors {
+            for &to in targets {
+                in_degree[to as usize] += 1;
+            }
+        }
+        let mut ready: VecDeque<usize> = (0..self.len()).filter(|&id| in_degree[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(self.nodes()[id].clone());
+            for next in self.targets(id) {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        // Every node left over still has a leftover predecessor, so walking backwards through
+        // leftovers must eventually revisit one
+        let mut predecessor = vec![None; self.len()];
+        for from in (0..self.len()).filter(|&from| in_degree[from] > 0) {
+            for to in self.targets(from) {
+                predecessor[to] = Some(from);
+            }
+        }
+        let mut id = (0..self.len()).find(|&id| in_degree[id] > 0).unwrap();
+        let mut position = HashMap::new();
+        let mut walk = Vec::new();
+        while !position.contains_key(&id) {
+            position.insert(id, walk.len());
+            walk.push(id);
+            id = predecessor[id].unwrap();
+        }
+        let mut cycle: Vec<N> = walk[position[&id]..].iter().map(|&id| self.nodes()[id].clone()).collect();
+        cycle.reverse();
+        Err(CycleError { cycle })
+    }
+
+    // Tarjan's algorithm, iterative so deep graphs cannot overflow the stack. Components come
+    // out in reverse topological order of the condensed graph.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        let n = self.len();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            let mut calls = vec![(root, 0)];
+            while let Some(&mut (id, ref mut edge)) = calls.last_mut() {
+                if *edge == 0 {
+                    index[id] = counter;
+                    low[id] = counter;
+                    counter += 1;
+                    stack.push(id);
+                    on_stack[id] = true;
+                }
+                if let Some(next) = self.successors[id].get(*edge).map(|&next| next as usize) {
+                    *edge += 1;
+                    if index[next] == usize::MAX {
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        low[id] = low[id].min(index[next]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[id]);
+                }
+                if low[id] == index[id] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(self.nodes()[member].clone());
+                        if member == id {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    // The smallest graph with the same reachability; only defined for acyclic graphs
+    pub fn transitive_reduction(&self) -> Result<Graph<N>, CycleError<N>> {
+        self.topological_sort()?;
+        let mut reduced = Graph::directed_with(self.nodes.clone());
+        for (from, targets) in self.successors.iter().enumerate() {
+            let indirect = self.reachable_ids(targets.iter().flat_map(|&t| self.targets(t as usize)));
+            let mut kept = HashSet::new();
+            for &to in targets {
+                if !indirect[to as usize] && kept.insert(to) {
+                    reduced.add_edge_ids(from as Id, to);
+                }
+            }
+        }
+        Ok(reduced)
+    }
+}
+
+impl<N: Clone + Eq + Hash + Display> Graph<N> {
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        let quote = |node: &N| format!("\"{}\"", node.to_string().replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = format!("{} {{\n", keyword);
+        for node in self.nodes() {
+            writeln!(dot, "    {};", quote(node)).unwrap();
+        }
+        for (from, to) in self.edges() {
+            writeln!(dot, "    {} {} {};", quote(from), arrow, quote(to)).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod structure {
+        use crate::graph::Graph;
+        use crate::interner::Interner;
+
+        #[test]
+        fn edges_keep_order() {
+            let graph = Graph::directed_from([("AAA", "BBB"), ("AAA", "CCC"), ("BBB", "DDD")]);
+            assert_eq!(4, graph.len());
+            assert_eq!(vec![&"BBB", &"CCC"], graph.successors(&"AAA").collect::<Vec<_>>());
+            assert_eq!(0, graph.successors(&"DDD").count());
+            assert_eq!(Some(3), graph.node_id(&"DDD"));
+        }
+
+        #[test]
+        fn undirected_edges() {
+            let graph = Graph::undirected_from([(1, 2), (2, 3)]);
+            assert_eq!(vec![&1, &3], graph.successors(&2).collect::<Vec<_>>());
+            assert_eq!(2, graph.edges().count());
+        }
+
+        #[test]
+        fn interned_ids() {
+            let mut names = Interner::new();
+            let (a, b, c) = (names.intern_str("a"), names.intern_str("b"), names.intern_str("c"));
+            let mut graph = Graph::directed_with(names);
+            graph.extend_ids([(a, c), (a, b)]);
+            assert_eq!(3, graph.len());
+            assert_eq!(&[c, b], graph.successor_ids(a));
+            assert_eq!("c", graph.node(graph.successor_ids(a)[0]));
+            assert_eq!(Some(b), graph.node_id("b"));
+        }
+
+        #[test]
+        fn reachability() {
+            let graph = Graph::directed_from([(1, 2), (2, 3), (4, 1)]);
+            let mut reached = graph.reachable_from(&1).into_iter().collect::<Vec<_>>();
+            reached.sort();
+            assert_eq!(vec![1, 2, 3], reached);
+            assert!(graph.reachable_from(&9).is_empty());
+        }
+    }
+
+    mod ordering {
+        use crate::graph::{CycleError, Graph};
+
+        #[test]
+        fn topological_sort() {
+            let graph = Graph::directed_from([(75, 47), (97, 75), (47, 53), (97, 47)]);
+            assert_eq!(Ok(vec![97, 75, 47, 53]), graph.topological_sort());
+        }
+
+        #[test]
+        fn reports_cycle() {
+            let graph = Graph::directed_from([(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+            let error = graph.topological_sort().unwrap_err();
+            let start = error.cycle.iter().position(|&n| n == 1).unwrap();
+            let mut cycle = error.cycle.clone();
+            cycle.rotate_left(start);
+            assert_eq!(vec![1, 2, 3], cycle);
+            assert_eq!(CycleError { cycle: vec![2] }.to_string(), "cycle through 2");
+        }
+
+        #[test]
+        fn strongly_connected() {
+            let graph = Graph::directed_from([(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)]);
+            let mut components = graph.strongly_connected_components()
+                .into_iter()
+                .map(|mut c| {
+                    c.sort();
+                    c
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(vec![4, 5], components[0]);
+            components.sort();
+            assert_eq!(vec![vec![1, 2, 3], vec![4, 5], vec![6]], components);
+        }
+
+        #[test]
+        fn transitive_reduction() {
+            let graph = Graph::directed_from([('a', 'b'), ('b', 'c'), ('a', 'c'), ('a', 'd'), ('c', 'd')]);
+            let reduced = graph.transitive_reduction().unwrap();
+            assert_eq!(
+                vec![(&'a', &'b'), (&'b', &'c'), (&'c', &'d')],
+                reduced.edges().collect::<Vec<_>>()
+            );
+            assert!(Graph::directed_from([(1, 2), (2, 1)]).transitive_reduction().is_err());
+        }
+    }
+
+    mod dot {
+        use crate::graph::Graph;
+
+        #[test]
+        fn output() {
+            let graph = Graph::directed_from([("a", "b\"c")]);
+            assert_eq!("digraph {\n    \"a\";\n    \"b\\\"c\";\n    \"a\" -> \"b\\\"c\";\n}\n", graph.to_dot());
+            let graph = Graph::undirected_from([(1, 2)]);
+            assert_eq!("graph {\n    \"1\";\n    \"2\";\n    \"1\" -- \"2\";\n}\n", graph.to_dot());
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use crate::coord::Point;
+use crate::read_string;
+
+pub type Cell = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1), (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+// What bounded, sparse and wrapping grids have in common. Everything is addressed by Point,
+// since only a bounded grid has a natural (row, column) origin.
+pub trait Surface<T> {
+    fn at(&self, point: Point) -> Option<&T>;
+
+    // Where a point lives on this surface: itself, its wrapped position, or None if it is off
+    // the edge
+    fn canonical(&self, point: Point) -> Option<Point>;
+
+    // The top-left and bottom-right corners of the area worth drawing, if there is one
+    fn extent(&self) -> Option<(Point, Point)>;
+
+    fn adjacent4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours4().filter_map(|p| self.canonical(p))
+    }
+
+    fn adjacent8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours8().filter_map(|p| self.canonical(p))
+    }
+
+    fn render_with(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        let mut s = String::new();
+        if let Some((min, max)) = self.extent() {
+            for y in min.y..=max.y {
+                s.extend((min.x..=max.x).map(|x| f(self.at(Point::new(x, y)))));
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(height: usize, width: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { cells: vec![fill; height * width], height, width }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        let mut cells = Vec::with_capacity(height * width);
+        for (r, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                panic!("Row {} has width {}, expected {}", r, row.len(), width);
+            }
+            cells.extend(row);
+        }
+        Grid { cells, height, width }
+    }
+
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let rows = input
+            .lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect();
+        Self::from_rows(rows)
+    }
+
+    pub fn read(filename: &str, f: impl FnMut(char) -> T) -> Self {
+        Self::parse(read_string(filename).as_str(), f)
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        cell.0 < self.height && cell.1 < self.width
+    }
+
+    pub fn get(&self, cell: Cell) -> Option<&T> {
+        if self.contains(cell) {
+            Some(&self.cells[cell.0 * self.width + cell.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, cell: Cell) -> Option<&mut T> {
+        if self.contains(cell) {
+            Some(&mut self.cells[cell.0 * self.width + cell.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, cell: Cell, value: T) -> bool {
+        if let Some(existing) = self.get_mut(cell) {
+            *existing = value;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn at(&self, point: Point) -> Option<&T> {
+        self.get(point.to_cell()?)
+    }
+
+    pub fn at_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.get_mut(point.to_cell()?)
+    }
+
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.at(point).is_some()
+    }
+
+    pub fn offset(&self, cell: Cell, delta: (isize, isize)) -> Option<Cell> {
+        let moved = (
+            cell.0.checked_add_signed(delta.0)?,
+            cell.1.checked_add_signed(delta.1)?,
+        );
+        if self.contains(moved) {
+            Some(moved)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, c: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(c).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|c| self.column(c))
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (Cell, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| ((i / width, i % width), value))
+    }
+
+    pub fn neighbours4(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        ORTHOGONAL.iter().filter_map(move |&delta| self.offset(cell, delta))
+    }
+
+    pub fn neighbours8(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        SURROUNDING.iter().filter_map(move |&delta| self.offset(cell, delta))
+    }
+
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Cell> {
+        self.cells().find(|(_, value)| predicate(value)).map(|(cell, _)| cell)
+    }
+
+    pub fn find_all<'a>(&'a self, predicate: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Cell> + 'a {
+        self.cells().filter(move |(_, value)| predicate(value)).map(|(cell, _)| cell)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut s = String::with_capacity(self.height * (self.width + 1));
+        for row in self.rows() {
+            s.extend(row.iter().map(&f));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Surface<T> for Grid<T> {
+    fn at(&self, point: Point) -> Option<&T> {
+        Grid::at(self, point)
+    }
+
+    fn canonical(&self, point: Point) -> Option<Point> {
+        self.contains_point(point).then_some(point)
+    }
+
+    fn extent(&self) -> Option<(Point, Point)> {
+        (self.height > 0 && self.width > 0)
+            .then(|| (Point::ORIGIN, Point::new(self.width as isize - 1, self.height as isize - 1)))
+    }
+}
+
+impl<T> Index<Cell> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, cell: Cell) -> &T {
+        match self.get(cell) {
+            Some(value) => value,
+            None => panic!("{:?} is outside of {}x{} grid", cell, self.height, self.width),
+        }
+    }
+}
+
+impl<T> IndexMut<Cell> for Grid<T> {
+    fn index_mut(&mut self, cell: Cell) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.get_mut(cell) {
+            Some(value) => value,
+            None => panic!("{:?} is outside of {}x{} grid", cell, height, width),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.at(point) {
+            Some(value) => value,
+            None => panic!("{} is outside of {}x{} grid", point, self.height, self.width),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.at_mut(point) {
+            Some(value) => value,
+            None => panic!("{} is outside of {}x{} grid", point, height, width),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse {
+        use crate::grid::Grid;
+
+        #[test]
+        fn dimensions() {
+            let grid = Grid::parse("ab\ncd\nef\n", |c| c);
+            assert_eq!(3, grid.height());
+            assert_eq!(2, grid.width());
+            assert_eq!('d', grid[(1, 1)]);
+        }
+
+        #[test]
+        fn from_file() {
+            let grid = Grid::read("input/2024-04-e1.txt", |c| c);
+            assert_eq!(5, grid.height());
+            assert_eq!(6, grid.width());
+        }
+
+        #[test]
+        #[should_panic]
+        fn ragged() {
+            Grid::parse("abc\nde", |c| c);
+        }
+    }
+
+    mod access {
+        use crate::coord::Point;
+        use crate::grid::Grid;
+
+        #[test]
+        fn bounds_checked() {
+            let mut grid = Grid::new(2, 3, 0);
+            assert!(grid.set((1, 2), 5));
+            assert!(!grid.set((2, 0), 5));
+            assert_eq!(Some(&5), grid.get((1, 2)));
+            assert_eq!(None, grid.get((0, 3)));
+        }
+
+        #[test]
+        fn points() {
+            let grid = Grid::parse("ab
+cd", |c| c);
+            assert_eq!('c', grid[Point::new(0, 1)]);
+            assert_eq!(None, grid.at(Point::new(-1, 0)));
+            assert!(!grid.contains_point(Point::new(2, 0)));
+        }
+
+        #[test]
+        fn rows_and_columns() {
+            let grid = Grid::parse("abc\ndef", |c| c);
+            let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+            assert_eq!(vec!["abc", "def"], rows);
+            assert_eq!(&['d', 'e', 'f'], grid.row(1));
+            let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+            assert_eq!(vec!["ad", "be", "cf"], columns);
+        }
+
+        #[test]
+        fn find() {
+            let grid = Grid::parse("...\n.S.\n..S", |c| c);
+            assert_eq!(Some((1, 1)), grid.find(|&c| c == 'S'));
+            assert_eq!(vec![(1, 1), (2, 2)], grid.find_all(|&c| c == 'S').collect::<Vec<_>>());
+        }
+    }
+
+    mod neighbours {
+        use crate::grid::Grid;
+
+        #[test]
+        fn corner() {
+            let grid = Grid::new(3, 3, ());
+            assert_eq!(vec![(0, 1), (1, 0)], grid.neighbours4((0, 0)).collect::<Vec<_>>());
+            assert_eq!(3, grid.neighbours8((0, 0)).count());
+        }
+
+        #[test]
+        fn centre() {
+            let grid = Grid::new(3, 3, ());
+            assert_eq!(4, grid.neighbours4((1, 1)).count());
+            assert_eq!(8, grid.neighbours8((1, 1)).count());
+        }
+    }
+
+    mod display {
+        use crate::coord::Point;
+        use crate::grid::{Grid, Surface};
+
+        #[test]
+        fn round_trip() {
+            let input = "#.#\n.#.\n";
+            let grid = Grid::parse(input, |c| c);
+            assert_eq!(input, grid.to_string());
+            assert_eq!("X_X\n_X_\n", grid.render(|&c| if c == '#' { 'X' } else { '_' }));
+        }
+
+        #[test]
+        fn surface() {
+            let grid = Grid::parse("#.#\n.#.", |c| c);
+            assert_eq!(grid.render(|&c| c), grid.render_with(|c| *c.unwrap()));
+            assert_eq!(2, grid.adjacent4(Point::ORIGIN).count());
+        }
+    }
+}
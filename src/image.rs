@@ -0,0 +1,231 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::coord::Point;
+use crate::grid::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(255, 0, 0);
+    pub const GREEN: Rgb = Rgb(0, 160, 0);
+    pub const BLUE: Rgb = Rgb(0, 0, 255);
+
+    pub fn grey(level: u8) -> Rgb {
+        Rgb(level, level, level)
+    }
+
+    // Rec. 601 weights, in integer arithmetic
+    pub fn luminance(self) -> u8 {
+        ((299 * self.0 as u32 + 587 * self.1 as u32 + 114 * self.2 as u32) / 1000) as u8
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Image { width, height, pixels: vec![background; width * height] }
+    }
+
+    // Each cell becomes a scale × scale block of pixels
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, colour: impl Fn(&T) -> Rgb) -> Self {
+        let mut image = Image::new(grid.width() * scale, grid.height() * scale, Rgb::BLACK);
+        for ((r, c), value) in grid.cells() {
+            image.fill_block(c * scale, r * scale, scale, colour(value));
+        }
+        image
+    }
+
+    // Points outside the width × height area are left out
+    pub fn from_points(
+        points: impl IntoIterator<Item = Point>,
+        width: usize,
+        height: usize,
+        scale: usize,
+        foreground: Rgb,
+        background: Rgb,
+    ) -> Self {
+        let mut image = Image::new(width * scale, height * scale, background);
+        for point in points {
+            if let Some((r, c)) = point.to_cell_within(height, width) {
+                image.fill_block(c * scale, r * scale, scale, foreground);
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour;
+        }
+    }
+
+    fn fill_block(&mut self, x: usize, y: usize, size: usize, colour: Rgb) {
+        for dy in 0..size {
+            for dx in 0..size {
+                self.set(x + dx, y + dy, colour);
+            }
+        }
+    }
+
+    fn draw(&mut self, other: &Image, x: usize, y: usize) {
+        for (i, &pixel) in other.pixels.iter().enumerate() {
+            self.set(x + i % other.width, y + i / other.width, pixel);
+        }
+    }
+
+    // Binary P6
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]));
+        bytes
+    }
+
+    // Binary P5, converting each pixel to its luminance
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut bytes = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().map(|p| p.luminance()));
+        bytes
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_ppm())
+    }
+
+    pub fn write_pgm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_pgm())
+    }
+}
+
+// Lays frames out left to right, top to bottom, with `gap` background pixels between them
+pub fn contact_sheet(frames: &[Image], columns: usize, gap: usize, background: Rgb) -> Image {
+    let columns = columns.max(1).min(frames.len().max(1));
+    let rows = frames.len().div_ceil(columns);
+    let frame_width = frames.iter().map(|f| f.width).max().unwrap_or(0);
+    let frame_height = frames.iter().map(|f| f.height).max().unwrap_or(0);
+    let mut sheet = Image::new(
+        columns * frame_width + (columns + 1) * gap,
+        rows * frame_height + (rows + 1) * gap,
+        background,
+    );
+    for (i, frame) in frames.iter().enumerate() {
+        let x = gap + (i % columns) * (frame_width + gap);
+        let y = gap + (i / columns) * (frame_height + gap);
+        sheet.draw(frame, x, y);
+    }
+    sheet
+}
+
+// Writes prefix-00000.ppm, prefix-00001.ppm, ... so the files sort in frame order
+pub struct FrameWriter {
+    directory: PathBuf,
+    prefix: String,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new(directory: impl Into<PathBuf>, prefix: &str) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(FrameWriter { directory, prefix: prefix.to_string(), next: 0 })
+    }
+
+    pub fn write(&mut self, frame: &Image) -> io::Result<PathBuf> {
+        let path = self.directory.join(format!("{}-{:05}.ppm", self.prefix, self.next));
+        frame.write_ppm(&path)?;
+        self.next += 1;
+        Ok(path)
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod image {
+        use crate::coord::Point;
+        use crate::grid::Grid;
+        use crate::image::{Image, Rgb};
+
+        #[test]
+        fn ppm_bytes() {
+            let grid = Grid::parse("#.\n.#", |c| c == '#');
+            let image = Image::from_grid(&grid, 1, |&wall| if wall { Rgb::RED } else { Rgb::WHITE });
+            let mut expected = b"P6\n2 2\n255\n".to_vec();
+            expected.extend([255, 0, 0, 255, 255, 255, 255, 255, 255, 255, 0, 0]);
+            assert_eq!(expected, image.to_ppm());
+        }
+
+        #[test]
+        fn pgm_luminance() {
+            let image = Image::from_points([Point::new(1, 0)], 2, 1, 1, Rgb::WHITE, Rgb::BLUE);
+            let mut expected = b"P5\n2 1\n255\n".to_vec();
+            expected.extend([29, 255]);
+            assert_eq!(expected, image.to_pgm());
+        }
+
+        #[test]
+        fn scaling() {
+            let points = [Point::new(1, 1), Point::new(5, 5)];
+            let image = Image::from_points(points, 3, 2, 3, Rgb::WHITE, Rgb::BLACK);
+            assert_eq!((9, 6), (image.width(), image.height()));
+            assert_eq!(Some(Rgb::WHITE), image.get(3, 3));
+            assert_eq!(Some(Rgb::WHITE), image.get(5, 5));
+            assert_eq!(Some(Rgb::BLACK), image.get(6, 3));
+            assert_eq!(Some(Rgb::BLACK), image.get(2, 3));
+        }
+    }
+
+    mod frames {
+        use std::fs;
+        use crate::image::{contact_sheet, FrameWriter, Image, Rgb};
+
+        #[test]
+        fn sheet_layout() {
+            let frames: Vec<Image> = (0..5u8).map(|i| Image::new(2, 2, Rgb::grey(i * 50))).collect();
+            let sheet = contact_sheet(&frames, 3, 1, Rgb::RED);
+            assert_eq!((10, 7), (sheet.width(), sheet.height()));
+            assert_eq!(Some(Rgb::RED), sheet.get(0, 0));
+            assert_eq!(Some(Rgb::grey(0)), sheet.get(1, 1));
+            assert_eq!(Some(Rgb::grey(100)), sheet.get(7, 2));
+            assert_eq!(Some(Rgb::grey(200)), sheet.get(4, 5));
+            assert_eq!(Some(Rgb::RED), sheet.get(7, 5));
+        }
+
+        #[test]
+        fn numbered_files() {
+            let directory = std::env::temp_dir().join(format!("frames-{}", std::process::id()));
+            let mut writer = FrameWriter::new(&directory, "step").unwrap();
+            let first = writer.write(&Image::new(1, 1, Rgb::BLACK)).unwrap();
+            let second = writer.write(&Image::new(1, 1, Rgb::WHITE)).unwrap();
+            assert_eq!(directory.join("step-00000.ppm"), first);
+            assert_eq!(directory.join("step-00001.ppm"), second);
+            assert_eq!(2, writer.frames_written());
+            assert_eq!(b"P6\n1 1\n255\n\xff\xff\xff".to_vec(), fs::read(&second).unwrap());
+            fs::remove_dir_all(directory).unwrap();
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub type Id = u32;
+
+// Dense ids for values, handed out in the order values are first seen, with lookups both ways
+#[derive(Clone, Debug)]
+pub struct Interner<T = String> {
+    ids: HashMap<T, Id>,
+    values: Vec<T>,
+}
+
+impl<T: Clone + Eq + Hash> Interner<T> {
+    pub fn new() -> Self {
+        Interner { ids: HashMap::new(), values: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn intern(&mut self, value: T) -> Id {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+        let id = Id::try_from(self.values.len()).expect("Too many values to intern");
+        self.ids.insert(value.clone(), id);
+        self.values.push(value);
+        id
+    }
+
+    pub fn get<Q>(&self, value: &Q) -> Option<Id>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(value).copied()
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.contains_key(value)
+    }
+
+    pub fn resolve(&self, id: Id) -> &T {
+        &self.values[id as usize]
+    }
+
+    // Indexed by id
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.values.iter().enumerate().map(|(i, value)| (i as Id, value))
+    }
+}
+
+impl Interner<String> {
+    // Only allocates for a name that has not been seen before
+    pub fn intern_str(&mut self, name: &str) -> Id {
+        match self.get(name) {
+            Some(id) => id,
+            None => self.intern(name.to_string()),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Default for Interner<T> {
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod interner {
+        use crate::interner::Interner;
+
+        #[test]
+        fn both_ways() {
+            let mut names = Interner::new();
+            assert_eq!(0, names.intern_str("broadcaster"));
+            assert_eq!(1, names.intern_str("inv"));
+            assert_eq!(0, names.intern("broadcaster".to_string()));
+            assert_eq!(2, names.len());
+            assert_eq!(Some(1), names.get("inv"));
+            assert_eq!(None, names.get("con"));
+            assert_eq!("inv", names.resolve(1));
+            assert_eq!(vec![(0, "broadcaster"), (1, "inv")],
+                       names.iter().map(|(id, name)| (id, name.as_str())).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn any_value() {
+            let mut cells = Interner::new();
+            let ids: Vec<_> = [(0, 1), (2, 3), (0, 1)].into_iter().map(|cell| cells.intern(cell)).collect();
+            assert_eq!(vec![0, 1, 0], ids);
+            assert_eq!(&(2, 3), cells.resolve(1));
+        }
+    }
+}
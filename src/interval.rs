@@ -0,0 +1,305 @@
+use std::ops::{Add, Range, Sub};
+
+pub trait Integer: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Integer for T {}
+
+// Half-open ranges kept sorted, disjoint and with no two touching, so equal sets compare equal
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<T>>) -> Self {
+        let mut ranges: Vec<Range<T>> = ranges.into_iter().filter(|r| r.start < r.end).collect();
+        ranges.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        IntervalSet { ranges: merged }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(index).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.union(&IntervalSet::from_ranges([range]));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        IntervalSet::from_ranges(self.ranges.iter().chain(other.ranges.iter()).cloned())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        for range in &self.ranges {
+            let mut start = range.start;
+            for cut in other.ranges.iter().filter(|c| c.start < range.end && c.end > range.start) {
+                if cut.start > start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    // Everything below the value, then everything at or above it
+    pub fn split_at(&self, value: T) -> (Self, Self) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for range in &self.ranges {
+            if range.end <= value {
+                below.push(range.clone());
+            } else if range.start >= value {
+                above.push(range.clone());
+            } else {
+                below.push(range.start..value);
+                above.push(value..range.end);
+            }
+        }
+        (IntervalSet { ranges: below }, IntervalSet { ranges: above })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Piece<T> {
+    pub source: Range<T>,
+    pub destination: T,
+}
+
+impl<T: Integer> Piece<T> {
+    fn map(&self, value: T) -> T {
+        self.destination + (value - self.source.start)
+    }
+
+    fn image(&self) -> Range<T> {
+        self.destination..self.map(self.source.end)
+    }
+}
+
+// Maps each source range onto a range of the same length starting at its destination; values
+// outside every source range map to themselves
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    pieces: Vec<Piece<T>>,
+}
+
+impl<T: Integer> RangeMap<T> {
+    pub fn new() -> Self {
+        RangeMap { pieces: Vec::new() }
+    }
+
+    pub fn from_pieces(pieces: impl IntoIterator<Item = Piece<T>>) -> Self {
+        let mut map = RangeMap::new();
+        for piece in pieces {
+            map.insert(piece.source, piece.destination);
+        }
+        map
+    }
+
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        if source.start >= source.end {
+            return;
+        }
+        let index = self.pieces.partition_point(|p| p.source.start < source.start);
+        let overlaps_previous = index > 0 && self.pieces[index - 1].source.end > source.start;
+        let overlaps_next = self.pieces.get(index).is_some_and(|p| p.source.start < source.end);
+        if overlaps_previous || overlaps_next {
+            panic!("Source range overlaps an existing piece");
+        }
+        self.pieces.insert(index, Piece { source, destination });
+    }
+
+    pub fn pieces(&self) -> &[Piece<T>] {
+        &self.pieces
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let index = self.pieces.partition_point(|p| p.source.end <= value);
+        match self.pieces.get(index) {
+            Some(piece) if piece.source.start <= value => piece.map(value),
+            _ => value,
+        }
+    }
+
+    // Splits the range at piece boundaries, pairing each part with where its start lands
+    pub fn split(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut parts = Vec::new();
+        let mut start = range.start;
+        for piece in self.pieces.iter().filter(|p| p.source.start < range.end && p.source.end > range.start) {
+            if piece.source.start > start {
+                parts.push((start..piece.source.start, start));
+            }
+            let from = start.max(piece.source.start);
+            let to = range.end.min(piece.source.end);
+            parts.push((from..to, piece.map(from)));
+            start = to;
+        }
+        if start < range.end {
+            parts.push((start..range.end, start));
+        }
+        parts
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::from_ranges(set.ranges().iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(part, destination)| destination..destination + (part.end - part.start)))
+    }
+
+    // The map that applies self and then next
+    pub fn compose(&self, next: &RangeMap<T>) -> RangeMap<T> {
+        let mut pieces = Vec::new();
+        for piece in &self.pieces {
+            for (part, destination) in next.split(piece.image()) {
+                let source_start = piece.source.start + (part.start - piece.destination);
+                pieces.push(Piece { source: source_start..source_start + (part.end - part.start), destination });
+            }
+        }
+        // values self leaves alone are only moved by next
+        let domain = IntervalSet::from_ranges(self.pieces.iter().map(|p| p.source.clone()));
+        for piece in &next.pieces {
+            for range in IntervalSet::from_ranges([piece.source.clone()]).difference(&domain).ranges() {
+                pieces.push(Piece { source: range.clone(), destination: piece.map(range.start) });
+            }
+        }
+        RangeMap::from_pieces(pieces.into_iter().filter(|p| p.source.start != p.destination))
+    }
+
+    // Only meaningful when the map is one-to-one, so that the destinations never overlap
+    pub fn invert(&self) -> RangeMap<T> {
+        RangeMap::from_pieces(self.pieces.iter().map(|p| Piece { source: p.image(), destination: p.source.start }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod interval_set {
+        use crate::interval::IntervalSet;
+        use std::iter;
+        use std::ops::Range;
+
+        fn single(range: Range<u32>) -> Vec<Range<u32>> {
+            iter::once(range).collect()
+        }
+
+        #[test]
+        fn merging() {
+            let set = IntervalSet::from_ranges([5..8, 1..3, 2..4, 8..9, 10..10]);
+            assert_eq!(&[1..4, 5..9], set.ranges());
+            assert!(set.contains(3));
+            assert!(!set.contains(4));
+            assert_eq!(Some(1), set.min());
+        }
+
+        #[test]
+        fn set_operations() {
+            let a = IntervalSet::from_ranges([0u32..10, 20..30]);
+            let b = IntervalSet::from_ranges(iter::once(5u32..25));
+            assert_eq!(single(0..30), a.union(&b).ranges());
+            assert_eq!(&[5..10, 20..25], a.intersection(&b).ranges());
+            assert_eq!(&[0..5, 25..30], a.difference(&b).ranges());
+            assert_eq!(single(10..20), b.difference(&a).ranges());
+        }
+
+        #[test]
+        fn splitting() {
+            let set = IntervalSet::from_ranges([0..10, 20..30]);
+            let (below, above) = set.split_at(25);
+            assert_eq!(&[0..10, 20..25], below.ranges());
+            assert_eq!(single(25..30), above.ranges());
+        }
+    }
+
+    mod range_map {
+        use crate::interval::{IntervalSet, RangeMap};
+        use std::iter;
+
+        fn seed_to_soil() -> RangeMap<usize> {
+            let mut map = RangeMap::new();
+            map.insert(98..100, 50);
+            map.insert(50..98, 52);
+            map
+        }
+
+        #[test]
+        fn single_values() {
+            let map = seed_to_soil();
+            assert_eq!(81, map.get(79));
+            assert_eq!(14, map.get(14));
+            assert_eq!(51, map.get(99));
+        }
+
+        #[test]
+        fn mapping_ranges() {
+            let map = seed_to_soil();
+            assert_eq!(vec![(40..50, 40), (50..60, 52)], map.split(40..60));
+            let mapped = map.map_set(&IntervalSet::from_ranges(iter::once(95..102)));
+            assert_eq!(&[50..52, 97..102], mapped.ranges());
+        }
+
+        #[test]
+        fn composition() {
+            let first = seed_to_soil();
+            let mut second = RangeMap::new();
+            second.insert(0..15, 39);
+            second.insert(15..52, 0);
+            second.insert(52..54, 37);
+            let composed = first.compose(&second);
+            for value in 0..110 {
+                assert_eq!(second.get(first.get(value)), composed.get(value), "value {}", value);
+            }
+        }
+
+        #[test]
+        fn inversion() {
+            let map = seed_to_soil();
+            let inverse = map.invert();
+            for value in 0..110 {
+                assert_eq!(value, inverse.get(map.get(value)));
+            }
+        }
+    }
+}
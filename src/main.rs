@@ -1,6 +1,34 @@
+use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Lines, Read};
+use std::process;
 
+mod bitgrid;
+mod checked;
+mod combinatorics;
+mod coord;
+mod coord3;
+mod cycle;
+mod digits;
+mod flood;
+mod geometry;
+mod graph;
+mod grid;
+mod image;
+mod interval;
+mod interner;
+mod memo;
+mod number_theory;
+mod parse;
+mod pattern;
+mod polynomial;
+mod rational;
+mod search;
+mod sparse_grid;
+mod svg;
+mod torus;
+mod union_find;
+mod vm;
 mod year2023day1;
 mod year2023day2;
 mod year2023day3;
@@ -29,8 +57,56 @@ mod year2024day13;
 mod year2024day14;
 mod year2024day15;
 
+const USAGE: &str = "usage: RustAdventOfCode <year> <day> [input file] [--svg out.svg | --ppm out.ppm]";
+
 fn main() {
-    println!("Hello, world!");
+    let mut args = env::args().skip(1);
+    let mut positional = Vec::new();
+    let mut svg_path = None;
+    let mut ppm_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--svg" => svg_path = Some(args.next().unwrap_or_else(|| fail("--svg needs a file name"))),
+            "--ppm" => ppm_path = Some(args.next().unwrap_or_else(|| fail("--ppm needs a file name"))),
+            _ => positional.push(arg),
+        }
+    }
+    let (year, day) = match &positional[..] {
+        [year, day, ..] => (
+            year.parse::<usize>().unwrap_or_else(|_| fail(USAGE)),
+            day.parse::<usize>().unwrap_or_else(|_| fail(USAGE)),
+        ),
+        _ => fail(USAGE),
+    };
+    let input = positional.get(2).cloned().unwrap_or_else(|| format!("input/{}-{:02}-input.txt", year, day));
+
+    match (svg_path, ppm_path) {
+        (Some(path), _) => {
+            let svg = match (year, day) {
+                (2023, 10) => year2023day10::year2023day10::svg(&input),
+                (2024, 6) => year2024day6::year2024day6::svg(&input),
+                (2024, 8) => year2024day8::year2024day8::svg(&input),
+                (2024, 12) => year2024day12::svg(&input),
+                _ => fail(&format!("{} day {} has no SVG export", year, day)),
+            };
+            svg.write(&path).unwrap_or_else(|e| fail(&format!("Error writing {}: {}", path, e)));
+            println!("Wrote {}", path);
+        }
+        (None, Some(path)) => {
+            let image = match (year, day) {
+                (2024, 14) => year2024day14::tree_image(&input),
+                _ => fail(&format!("{} day {} has no PPM export", year, day)),
+            };
+            image.write_ppm(&path).unwrap_or_else(|e| fail(&format!("Error writing {}: {}", path, e)));
+            println!("Wrote {}", path);
+        }
+        (None, None) => println!("Answers are checked by `cargo test`; pass --svg out.svg or --ppm out.ppm to draw a supported day"),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
 }
 
 fn read_lines(file_name: &str) -> Lines<BufReader<File>> {
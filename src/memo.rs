@@ -0,0 +1,148 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+    pub entries: usize,
+    pub limit: Option<usize>,
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} hits, {} misses, {} entries", self.hits, self.misses, self.entries)?;
+        if let Some(limit) = self.limit {
+            write!(f, " (limit {}, {} evicted)", limit, self.evictions)?;
+        }
+        Ok(())
+    }
+}
+
+// A cache for recursive solvers. The closure given to get_or_insert_with receives the memo
+// back, so a recursive function can thread it through its own calls.
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    limit: Option<usize>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo { cache: HashMap::new(), limit: None, stats: MemoStats::default() }
+    }
+
+    // Once full, an arbitrary entry is dropped to make room for each new one
+    pub fn with_limit(limit: usize) -> Self {
+        assert!(limit > 0, "A memo needs room for at least one entry");
+        Memo { limit: Some(limit), stats: MemoStats { limit: Some(limit), ..MemoStats::default() }, ..Memo::new() }
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = self.cache.get(key).cloned();
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> V {
+        if let Some(limit) = self.limit {
+            if self.cache.len() >= limit && !self.cache.contains_key(&key) {
+                let victim = self.cache.keys().next().cloned().unwrap();
+                self.cache.remove(&victim);
+                self.stats.evictions += 1;
+            }
+        }
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        match self.get(&key) {
+            Some(value) => value,
+            None => {
+                let value = compute(self);
+                self.insert(key, value)
+            }
+        }
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats { entries: self.cache.len(), ..self.stats }
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    // Prints the statistics to stderr when the AOC_TRACE environment variable is set
+    pub fn trace(&self, name: &str) {
+        if env::var_os("AOC_TRACE").is_some() {
+            eprintln!("[memo] {}: {}", name, self.stats());
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod memo {
+        use crate::memo::{Memo, MemoStats};
+
+        fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+            if n < 2 {
+                return n;
+            }
+            memo.get_or_insert_with(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+        }
+
+        #[test]
+        fn recursion() {
+            let mut memo = Memo::new();
+            assert_eq!(12586269025, fibonacci(&mut memo, 50));
+            let stats = memo.stats();
+            assert_eq!(49, stats.misses);
+            assert_eq!(47, stats.hits);
+            assert_eq!(49, stats.entries);
+        }
+
+        #[test]
+        fn borrowed_keys() {
+            let mut memo: Memo<String, usize> = Memo::new();
+            memo.insert("towel".to_string(), 5);
+            assert_eq!(Some(5), memo.get("towel"));
+            assert_eq!(None, memo.get("rug"));
+            assert_eq!("1 hits, 1 misses, 1 entries", memo.stats().to_string());
+        }
+
+        #[test]
+        fn size_limit() {
+            let mut memo = Memo::with_limit(2);
+            for n in 0..5 {
+                memo.insert(n, n * n);
+            }
+            assert_eq!(
+                MemoStats { hits: 0, misses: 0, evictions: 3, entries: 2, limit: Some(2) },
+                memo.stats()
+            );
+            assert_eq!("0 hits, 0 misses, 2 entries (limit 2, 3 evicted)", memo.stats().to_string());
+            assert_eq!(Some(16), memo.get(&4));
+        }
+    }
+}
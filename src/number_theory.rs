@@ -0,0 +1,277 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let temp = b;
+        b = a % b;
+        a = temp;
+    }
+    a
+}
+
+pub fn lcm(a: usize, b: usize) -> usize {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a * (b / gcd(a, b))
+}
+
+// Returns (g, x, y) such that a*x + b*y = g = gcd(a, b)
+pub fn extended_gcd(a: isize, b: isize) -> (isize, isize, isize) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+pub fn mod_inverse(a: isize, modulus: isize) -> Option<isize> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+// Solves x ≡ r (mod m) for every (r, m), returning (x, lcm of the moduli) with 0 <= x < lcm.
+// Moduli need not be coprime. None means the congruences contradict each other, a modulus is
+// not positive, or the combined modulus does not fit in an isize.
+pub fn crt(congruences: &[(isize, isize)]) -> Option<(isize, isize)> {
+    let mut x: i128 = 0;
+    let mut modulus: i128 = 1;
+    for &(r, m) in congruences {
+        if m <= 0 {
+            return None;
+        }
+        let (g, p, _) = extended_gcd(isize::try_from(modulus).ok()?, m);
+        let (r, m) = ((r as i128).rem_euclid(m as i128), m as i128);
+        let g = g as i128;
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let k = ((r - x) / g % step * (p as i128 % step)).rem_euclid(step);
+        x += modulus * k;
+        modulus *= step;
+        x = x.rem_euclid(modulus);
+    }
+    Some((isize::try_from(x).ok()?, isize::try_from(modulus).ok()?))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ModInt {
+    value: usize,
+    modulus: usize,
+}
+
+impl ModInt {
+    pub fn new(value: isize, modulus: usize) -> Self {
+        ModInt { value: value.rem_euclid(modulus as isize) as usize, modulus }
+    }
+
+    pub fn value(self) -> usize {
+        self.value
+    }
+
+    pub fn modulus(self) -> usize {
+        self.modulus
+    }
+
+    pub fn pow(self, mut exponent: usize) -> Self {
+        let mut base = self;
+        let mut result = ModInt::new(1, self.modulus);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(self.value as isize, self.modulus as isize)
+            .map(|inverse| ModInt::new(inverse, self.modulus))
+    }
+
+    fn check(self, other: ModInt) {
+        if self.modulus != other.modulus {
+            panic!("Mixing moduli {} and {}", self.modulus, other.modulus);
+        }
+    }
+}
+
+impl Add for ModInt {
+    type Output = ModInt;
+
+    fn add(self, rhs: ModInt) -> ModInt {
+        self.check(rhs);
+        ModInt { value: (self.value + rhs.value) % self.modulus, modulus: self.modulus }
+    }
+}
+
+impl Add<isize> for ModInt {
+    type Output = ModInt;
+
+    fn add(self, rhs: isize) -> ModInt {
+        self + ModInt::new(rhs, self.modulus)
+    }
+}
+
+impl AddAssign for ModInt {
+    fn add_assign(&mut self, rhs: ModInt) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for ModInt {
+    type Output = ModInt;
+
+    fn sub(self, rhs: ModInt) -> ModInt {
+        self.check(rhs);
+        ModInt { value: (self.value + self.modulus - rhs.value) % self.modulus, modulus: self.modulus }
+    }
+}
+
+impl SubAssign for ModInt {
+    fn sub_assign(&mut self, rhs: ModInt) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for ModInt {
+    type Output = ModInt;
+
+    fn mul(self, rhs: ModInt) -> ModInt {
+        self.check(rhs);
+        let value = (self.value as u128 * rhs.value as u128) % self.modulus as u128;
+        ModInt { value: value as usize, modulus: self.modulus }
+    }
+}
+
+impl Mul<isize> for ModInt {
+    type Output = ModInt;
+
+    fn mul(self, rhs: isize) -> ModInt {
+        self * ModInt::new(rhs, self.modulus)
+    }
+}
+
+impl MulAssign for ModInt {
+    fn mul_assign(&mut self, rhs: ModInt) {
+        *self = *self * rhs;
+    }
+}
+
+impl Neg for ModInt {
+    type Output = ModInt;
+
+    fn neg(self) -> ModInt {
+        ModInt::new(0, self.modulus) - self
+    }
+}
+
+impl Display for ModInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod euclid {
+        use crate::number_theory::{extended_gcd, gcd, lcm, mod_inverse};
+
+        #[test]
+        fn gcd_and_lcm() {
+            assert_eq!(6, gcd(48, 18));
+            assert_eq!(144, lcm(48, 18));
+            assert_eq!(7, gcd(0, 7));
+            assert_eq!(0, lcm(0, 7));
+        }
+
+        #[test]
+        fn bezout() {
+            let (g, x, y) = extended_gcd(240, 46);
+            assert_eq!(2, g);
+            assert_eq!(g, 240 * x + 46 * y);
+            let (g, x, y) = extended_gcd(-15, 35);
+            assert_eq!(5, g);
+            assert_eq!(g, -15 * x + 35 * y);
+        }
+
+        #[test]
+        fn inverse() {
+            assert_eq!(Some(4), mod_inverse(3, 11));
+            assert_eq!(Some(7), mod_inverse(-3, 11));
+            assert_eq!(None, mod_inverse(6, 9));
+        }
+    }
+
+    mod crt {
+        use crate::number_theory::crt;
+
+        #[test]
+        fn coprime() {
+            assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        }
+
+        #[test]
+        fn shared_factors() {
+            assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+            assert_eq!(None, crt(&[(1, 6), (2, 4)]));
+        }
+
+        #[test]
+        fn negative_residues() {
+            assert_eq!(Some((8, 15)), crt(&[(-1, 3), (-2, 5)]));
+        }
+
+        #[test]
+        fn out_of_range() {
+            assert_eq!(None, crt(&[(1, 0)]));
+            assert_eq!(None, crt(&[(1, 3), (1, -5)]));
+            let big = (1 << 40) + 1;
+            assert_eq!(None, crt(&[(1, big), (2, big + 2)]));
+            assert_eq!(None, crt(&[(0, big), (0, big + 2), (0, big + 4)]));
+        }
+    }
+
+    mod mod_int {
+        use crate::number_theory::ModInt;
+
+        #[test]
+        fn arithmetic() {
+            let a = ModInt::new(-3, 101);
+            assert_eq!(98, a.value());
+            assert_eq!(3, (-a).value());
+            assert_eq!(1, (a + 4).value());
+            assert_eq!(91, (a * 2 + ModInt::new(-4, 101)).value());
+            assert_eq!(1, (a * a.inverse().unwrap()).value());
+        }
+
+        #[test]
+        fn power() {
+            assert_eq!(1, ModInt::new(3, 7).pow(6).value());
+            assert_eq!(24, ModInt::new(2, 1000).pow(10).value());
+        }
+
+        #[test]
+        #[should_panic]
+        fn mixed_moduli() {
+            let _ = ModInt::new(1, 101) + ModInt::new(1, 103);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Lines and columns are 1-based; the line is filled in by whichever caller knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        ParseError { line: None, column, message: message.into() }
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        ParseError { line: Some(line), ..self }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}, column {}: {}", line, self.column, self.message),
+            None => write!(f, "column {}: {}", self.column, self.message),
+        }
+    }
+}
+
+// Fills `{}` placeholders in order, returning (field, column). Each field runs up to the next
+// occurrence of the literal text that follows it, or to the end of the input for a trailing one.
+pub fn scan_fields<'a>(template: &str, input: &'a str, expected: usize) -> Result<Vec<(&'a str, usize)>, ParseError> {
+    let literals: Vec<&str> = template.split("{}").collect();
+    if literals.len() - 1 != expected {
+        panic!("Template {:?} has {} fields but {} types were given", template, literals.len() - 1, expected);
+    }
+    if literals[1..literals.len() - 1].iter().any(|l| l.is_empty()) {
+        panic!("Template {:?} has two fields with nothing between them", template);
+    }
+
+    let mut fields = Vec::with_capacity(expected);
+    let mut position = 0;
+    for (i, literal) in literals.iter().enumerate() {
+        if i > 0 {
+            let end = if i == literals.len() - 1 && literal.is_empty() {
+                input.len()
+            } else {
+                match input[position..].find(literal) {
+                    Some(offset) => position + offset,
+                    None => return Err(ParseError::new(position + 1, format!("expected {:?}", literal))),
+                }
+            };
+            if end == position {
+                return Err(ParseError::new(position + 1, "expected a value"));
+            }
+            fields.push((&input[position..end], position + 1));
+            position = end;
+        }
+        if !input[position..].starts_with(literal) {
+            return Err(ParseError::new(position + 1, format!("expected {:?}", literal)));
+        }
+        position += literal.len();
+    }
+    if position != input.len() {
+        return Err(ParseError::new(position + 1, format!("unexpected {:?}", &input[position..])));
+    }
+    Ok(fields)
+}
+
+pub fn field<T: FromStr>((text, column): (&str, usize)) -> Result<T, ParseError> {
+    text.parse().map_err(|_| {
+        ParseError::new(column, format!("cannot read {:?} as {}", text, std::any::type_name::<T>()))
+    })
+}
+
+// scan!("p={},{} v={},{}", line => isize, isize, isize, isize) gives Result<(isize, ...), ParseError>
+macro_rules! scan {
+    ($template:expr, $input:expr => $($t:ty),+ $(,)?) => {{
+        let expected = [$(stringify!($t)),+].len();
+        $crate::parse::scan_fields($template, $input, expected).and_then(|fields| {
+            let mut fields = fields.into_iter();
+            Ok(($($crate::parse::field::<$t>(fields.next().unwrap())?,)+))
+        })
+    }};
+}
+pub(crate) use scan;
+
+// A '-' or '+' directly before digits is a sign unless it follows a digit, so "2-4" is 2 and 4
+pub fn integers<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = line.as_bytes();
+    let mut values = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let signed = (bytes[i] == b'-' || bytes[i] == b'+')
+            && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit())
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+        if signed || bytes[i].is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            values.push(field((&line[start..i], start + 1))?);
+        } else {
+            i += 1;
+        }
+    }
+    Ok(values)
+}
+
+pub struct Section<'a> {
+    pub first_line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.lines.iter().enumerate().map(|(i, &line)| (self.first_line + i, line))
+    }
+
+    pub fn parse_lines<T>(&self, f: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        parse_numbered(self.numbered_lines(), f)
+    }
+}
+
+// Groups of lines separated by one or more blank lines
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current: Option<Section> = None;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            sections.extend(current.take());
+        } else {
+            current.get_or_insert(Section { first_line: i + 1, lines: Vec::new() }).lines.push(line);
+        }
+    }
+    sections.extend(current);
+    sections
+}
+
+pub fn parse_lines<'a, T>(input: &'a str, f: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    parse_numbered(input.lines().enumerate().map(|(i, line)| (i + 1, line)), f)
+}
+
+fn parse_numbered<'a, T>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines.map(|(number, line)| f(line).map_err(|e| e.at_line(number))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod scan {
+        use crate::parse::ParseError;
+
+        #[test]
+        fn typed_fields() {
+            let robot = scan!("p={},{} v={},{}", "p=0,4 v=3,-3" => isize, isize, isize, isize);
+            assert_eq!(Ok((0, 4, 3, -3)), robot);
+            let node = scan!("{} = ({}, {})", "AAA = (BBB, CCC)" => String, String, String);
+            assert_eq!(Ok(("AAA".to_string(), "BBB".to_string(), "CCC".to_string())), node);
+        }
+
+        #[test]
+        fn located_errors() {
+            let bad_number = scan!("p={},{}", "p=0,x4" => isize, isize);
+            assert_eq!(Err(ParseError::new(5, "cannot read \"x4\" as isize")), bad_number);
+            let missing_literal = scan!("{}: {}", "190 10 19" => usize, String);
+            assert_eq!(Some(1), missing_literal.err().map(|e| e.column));
+            let trailing = scan!("x={}!", "x=1!?" => usize);
+            assert_eq!("column 5: unexpected \"?\"", trailing.unwrap_err().to_string());
+        }
+    }
+
+    mod integers {
+        use crate::parse::integers;
+
+        #[test]
+        fn signs_and_separators() {
+            assert_eq!(Ok(vec![-3, 4, 12, 2, 4, 7]), integers::<i32>("x=-3, y=+4 12 2-4 (7)"));
+            assert_eq!(Ok(vec![10000000000u64]), integers("Prize: X=10000000000"));
+        }
+
+        #[test]
+        fn overflow_is_located() {
+            let error = integers::<u8>("1 2 300").unwrap_err();
+            assert_eq!(5, error.column);
+        }
+    }
+
+    mod sections {
+        use crate::parse::{integers, parse_lines, sections};
+
+        #[test]
+        fn blank_line_groups() {
+            let input = "a\nb\n\n\nc\n  \nd\ne\n";
+            let groups = sections(input);
+            assert_eq!(3, groups.len());
+            assert_eq!(vec!["a", "b"], groups[0].lines);
+            assert_eq!(5, groups[1].first_line);
+            assert_eq!(vec![(7, "d"), (8, "e")], groups[2].numbered_lines().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn errors_carry_line_numbers() {
+            let error = parse_lines("1 2\n3 4\n5 x999999999999999999999", integers::<i32>).unwrap_err();
+            assert_eq!("line 3, column 4: cannot read \"999999999999999999999\" as i32", error.to_string());
+        }
+    }
+}
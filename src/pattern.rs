@@ -0,0 +1,136 @@
+use crate::grid::{Cell, Grid};
+
+// Quarter turns clockwise, applied after an optional left-right mirror
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub reflected: bool,
+    pub quarter_turns: u8,
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation { reflected: false, quarter_turns: 0 };
+
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true].into_iter()
+            .flat_map(|reflected| (0..4).map(move |quarter_turns| Orientation { reflected, quarter_turns }))
+    }
+
+    pub fn apply<T: Clone>(self, grid: &Grid<T>) -> Grid<T> {
+        let mut rows: Vec<Vec<T>> = grid.rows().map(|row| row.to_vec()).collect();
+        if self.reflected {
+            rows.iter_mut().for_each(|row| row.reverse());
+        }
+        let mut result = Grid::from_rows(rows);
+        for _ in 0..self.quarter_turns % 4 {
+            let (h, w) = (result.height(), result.width());
+            let rotated = (0..w).map(|r| (0..h).map(|c| result[(h - 1 - c, r)].clone()).collect()).collect();
+            result = Grid::from_rows(rotated);
+        }
+        result
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    // The top-left corner of the oriented pattern
+    pub position: Cell,
+    pub orientation: Orientation,
+}
+
+// A rectangular pattern where None matches anything. Orientations that produce the same
+// pattern, such as a mirror of a symmetric shape, are only searched once.
+#[derive(Clone, Debug)]
+pub struct Pattern<T> {
+    variants: Vec<(Orientation, Grid<Option<T>>)>,
+}
+
+impl<T: Clone + PartialEq> Pattern<T> {
+    pub fn new(pattern: Grid<Option<T>>) -> Self {
+        Pattern::with_orientations(pattern, Orientation::all())
+    }
+
+    // Only the given orientations, e.g. just IDENTITY for a fixed search
+    pub fn with_orientations(pattern: Grid<Option<T>>, orientations: impl IntoIterator<Item = Orientation>) -> Self {
+        let mut variants: Vec<(Orientation, Grid<Option<T>>)> = Vec::new();
+        for orientation in orientations {
+            let oriented = orientation.apply(&pattern);
+            if !variants.iter().any(|(_, existing)| *existing == oriented) {
+                variants.push((orientation, oriented));
+            }
+        }
+        Pattern { variants }
+    }
+
+    pub fn orientations(&self) -> impl Iterator<Item = Orientation> + '_ {
+        self.variants.iter().map(|&(orientation, _)| orientation)
+    }
+
+    fn matches_at(haystack: &Grid<T>, pattern: &Grid<Option<T>>, (r, c): Cell) -> bool {
+        pattern.cells().all(|((pr, pc), expected)| {
+            expected.as_ref().is_none_or(|expected| haystack[(r + pr, c + pc)] == *expected)
+        })
+    }
+
+    pub fn find_all<'a>(&'a self, haystack: &'a Grid<T>) -> impl Iterator<Item = Match> + 'a {
+        self.variants.iter().flat_map(move |(orientation, pattern)| {
+            let rows = (haystack.height() + 1).saturating_sub(pattern.height());
+            let columns = (haystack.width() + 1).saturating_sub(pattern.width());
+            (0..rows)
+                .flat_map(move |r| (0..columns).map(move |c| (r, c)))
+                .filter(move |&position| Self::matches_at(haystack, pattern, position))
+                .map(move |position| Match { position, orientation: *orientation })
+        })
+    }
+
+    pub fn count(&self, haystack: &Grid<T>) -> usize {
+        self.find_all(haystack).count()
+    }
+}
+
+impl Pattern<char> {
+    pub fn parse(text: &str, wildcard: char) -> Self {
+        Pattern::new(Grid::parse(text, |c| (c != wildcard).then_some(c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod orientation {
+        use crate::grid::Grid;
+        use crate::pattern::Orientation;
+
+        #[test]
+        fn transforms() {
+            let grid = Grid::parse("ab\ncd\nef", |c| c);
+            let turn = |reflected, quarter_turns| Orientation { reflected, quarter_turns }.apply(&grid).to_string();
+            assert_eq!("eca\nfdb\n", turn(false, 1));
+            assert_eq!("fe\ndc\nba\n", turn(false, 2));
+            assert_eq!("ba\ndc\nfe\n", turn(true, 0));
+            assert_eq!("fdb\neca\n", turn(true, 1));
+            assert_eq!(8, Orientation::all().count());
+        }
+    }
+
+    mod search {
+        use crate::grid::Grid;
+        use crate::pattern::{Match, Orientation, Pattern};
+
+        #[test]
+        fn deduplicates() {
+            assert_eq!(1, Pattern::parse("#.#\n.#.\n#.#", '?').orientations().count());
+            assert_eq!(4, Pattern::parse("ab\nab", '?').orientations().count());
+            assert_eq!(8, Pattern::parse("ab\nc?", '?').orientations().count());
+        }
+
+        #[test]
+        fn finds() {
+            let haystack = Grid::parse("xab.\n.cxb\n..ca", |c| c);
+            let pattern = Pattern::parse("ab\nc?", '?');
+            let matches: Vec<Match> = pattern.find_all(&haystack).collect();
+            assert_eq!(2, matches.len());
+            assert_eq!(Match { position: (0, 1), orientation: Orientation::IDENTITY }, matches[0]);
+            assert_eq!((1, 2), matches[1].position);
+            assert_eq!(0, pattern.count(&Grid::parse("a", |c| c)));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::rational::Rational;
+
+// Repeated differences of evenly spaced samples, down to the first row of zeros. When the
+// samples come from a polynomial, the first column of the table determines it completely.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifferenceTable {
+    levels: Vec<Vec<isize>>,
+}
+
+impl DifferenceTable {
+    pub fn new(samples: &[isize]) -> Self {
+        let mut levels = vec![samples.to_vec()];
+        while !levels.last().unwrap().iter().all(|&x| x == 0) {
+            let next = levels.last().unwrap().windows(2).map(|w| w[1] - w[0]).collect();
+            levels.push(next);
+        }
+        DifferenceTable { levels }
+    }
+
+    pub fn levels(&self) -> &[Vec<isize>] {
+        &self.levels
+    }
+
+    // None when the samples run out before the differences settle at zero, so no polynomial
+    // of lower degree than the sample count is evident
+    pub fn degree(&self) -> Option<usize> {
+        if self.levels.last().unwrap().is_empty() {
+            return None;
+        }
+        Some(self.levels.len().saturating_sub(2))
+    }
+
+    // Newton's forward formula, f(n) = sum of C(n, k) * first difference k, which holds for
+    // any integer n, including ones before the first sample
+    pub fn at(&self, n: isize) -> isize {
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+        for (k, level) in self.levels.iter().enumerate() {
+            let Some(&first) = level.first() else { break };
+            value += binomial * first as i128;
+            binomial = binomial * (n as i128 - k as i128) / (k as i128 + 1);
+        }
+        value.try_into().expect("Polynomial value does not fit in an isize")
+    }
+}
+
+// The value at x of the unique polynomial through the points, whose x values must differ
+pub fn interpolate(points: &[(Rational, Rational)], x: Rational) -> Rational {
+    points.iter().enumerate().fold(Rational::ZERO, |sum, (i, &(xi, yi))| {
+        let basis = points.iter().enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(Rational::ONE, |product, (_, &(xj, _))| product * (x - xj) / (xi - xj));
+        sum + yi * basis
+    })
+}
+
+// interpolate for integer samples, when the answer is known to be an integer
+pub fn interpolate_integer(points: &[(isize, isize)], x: isize) -> Option<isize> {
+    let points: Vec<_> = points.iter().map(|&(x, y)| (Rational::from(x), Rational::from(y))).collect();
+    interpolate(&points, Rational::from(x)).to_integer()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    mod differences {
+        use crate::polynomial::DifferenceTable;
+
+        #[test]
+        fn degree() {
+            assert_eq!(Some(1), DifferenceTable::new(&[0, 3, 6, 9, 12, 15]).degree());
+            assert_eq!(Some(2), DifferenceTable::new(&[1, 3, 6, 10, 15, 21]).degree());
+            assert_eq!(Some(0), DifferenceTable::new(&[7, 7, 7]).degree());
+            assert_eq!(None, DifferenceTable::new(&[1, 2, 4, 8]).degree());
+        }
+
+        #[test]
+        fn evaluates_anywhere() {
+            // n^2 + n + 1
+            let table = DifferenceTable::new(&[1, 3, 7, 13]);
+            for n in -5..20 {
+                assert_eq!(n * n + n + 1, table.at(n));
+            }
+            assert_eq!(702322373364591, table.at(26501365));
+        }
+    }
+
+    mod lagrange {
+        use crate::polynomial::{interpolate, interpolate_integer};
+        use crate::rational::Rational;
+
+        #[test]
+        fn uneven_samples() {
+            // 3x^2 - 2x + 5 sampled at uneven spacing
+            let f = |x: isize| 3 * x * x - 2 * x + 5;
+            let points: Vec<_> = [65, 196, 327].iter().map(|&x| (x, f(x))).collect();
+            assert_eq!(Some(f(26501365)), interpolate_integer(&points, 26501365));
+            let halves = [(Rational::ZERO, Rational::ZERO), (Rational::from(2isize), Rational::ONE)];
+            assert_eq!(Rational::new(1, 2), interpolate(&halves, Rational::ONE));
+            assert_eq!(None, interpolate_integer(&[(0, 0), (2, 1)], 1));
+        }
+    }
+}
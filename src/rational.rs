@@ -0,0 +1,351 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// None only when the gcd is 2^127, which does not fit in an i128
+fn gcd(mut a: i128, mut b: i128) -> Option<i128> {
+    while b != 0 {
+        (a, b) = (b, a.checked_rem(b).unwrap_or(0));
+    }
+    a.checked_abs()
+}
+
+// Always stored in lowest terms with a positive denominator, so derived equality is exact
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numerator: 0, denominator: 1 };
+    pub const ONE: Rational = Rational { numerator: 1, denominator: 1 };
+
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        Self::checked_new(numerator, denominator)
+            .unwrap_or_else(|| panic!("Invalid rational {}/{}", numerator, denominator))
+    }
+
+    // None for a zero denominator, or for i128::MIN in either part, which could not be negated
+    pub fn checked_new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 || numerator == i128::MIN || denominator == i128::MIN {
+            return None;
+        }
+        let g = gcd(numerator, denominator)?;
+        let sign = if denominator < 0 { -1 } else { 1 };
+        Some(Rational {
+            numerator: (numerator / g).checked_mul(sign)?,
+            denominator: (denominator / g).checked_mul(sign)?,
+        })
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let g = gcd(self.denominator, rhs.denominator)?;
+        let denominator = (self.denominator / g).checked_mul(rhs.denominator)?;
+        let numerator = self.numerator.checked_mul(rhs.denominator / g)?
+            .checked_add(rhs.numerator.checked_mul(self.denominator / g)?)?;
+        Rational::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        self.checked_add(Rational { numerator: rhs.numerator.checked_neg()?, ..rhs })
+    }
+
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        // cross-cancel first so intermediate products stay as small as possible
+        let g1 = gcd(self.numerator, rhs.denominator)?.max(1);
+        let g2 = gcd(rhs.numerator, self.denominator)?.max(1);
+        Rational::checked_new(
+            (self.numerator / g1).checked_mul(rhs.numerator / g2)?,
+            (self.denominator / g2).checked_mul(rhs.denominator / g1)?,
+        )
+    }
+
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        self.checked_mul(rhs.recip()?)
+    }
+
+    pub fn recip(self) -> Option<Rational> {
+        Rational::checked_new(self.denominator, self.numerator)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational { numerator: value, denominator: 1 }
+    }
+}
+
+impl From<isize> for Rational {
+    fn from(value: isize) -> Self {
+        Rational::from(value as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        self.checked_add(rhs).unwrap_or_else(|| panic!("Overflow adding {} and {}", self, rhs))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        self.checked_sub(rhs).unwrap_or_else(|| panic!("Overflow subtracting {} from {}", rhs, self))
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        self.checked_mul(rhs).unwrap_or_else(|| panic!("Overflow multiplying {} by {}", self, rhs))
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Rational) -> Rational {
+        self.checked_div(rhs).unwrap_or_else(|| panic!("Cannot divide {} by {}", self, rhs))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational::ZERO - self
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    // Denominators are positive, so cross-multiplying keeps the order. When the products
+    // overflow, compare continued fraction terms instead, which never grow.
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (Some(lhs), Some(rhs)) = (
+            self.numerator.checked_mul(other.denominator),
+            other.numerator.checked_mul(self.denominator),
+        ) {
+            return lhs.cmp(&rhs);
+        }
+        // a = q + r/d with 0 <= r < d; once the whole parts agree, comparing r/d is comparing
+        // the reciprocals d/r the other way round
+        let (mut a, mut b) = ((self.numerator, self.denominator), (other.numerator, other.denominator));
+        let mut flipped = false;
+        loop {
+            let whole = a.0.div_euclid(a.1).cmp(&b.0.div_euclid(b.1));
+            let (ra, rb) = (a.0.rem_euclid(a.1), b.0.rem_euclid(b.1));
+            let order = match whole {
+                Ordering::Equal if ra != 0 && rb != 0 => {
+                    (a, b) = ((a.1, ra), (b.1, rb));
+                    flipped = !flipped;
+                    continue;
+                }
+                Ordering::Equal => ra.cmp(&rb),
+                unequal => unequal,
+            };
+            return if flipped { order.reverse() } else { order };
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    // One solution with every free variable set to zero, plus which variables are free
+    Infinite { particular: Vec<Rational>, free: Vec<usize> },
+    None,
+}
+
+impl Solution {
+    pub fn integer_solution(&self) -> Option<Vec<i128>> {
+        match self {
+            Solution::Unique(values) => values.iter().map(|v| v.to_integer()).collect(),
+            _ => None,
+        }
+    }
+}
+
+// Solves coefficients * x = constants by Gauss-Jordan elimination, one row per equation.
+// None when an intermediate value overflows.
+pub fn solve(coefficients: &[Vec<Rational>], constants: &[Rational]) -> Option<Solution> {
+    let rows = coefficients.len();
+    let columns = coefficients.first().map_or(0, |row| row.len());
+    let mut matrix: Vec<Vec<Rational>> = coefficients.iter()
+        .zip(constants)
+        .map(|(row, &constant)| {
+            assert_eq!(columns, row.len(), "Ragged coefficient matrix");
+            row.iter().copied().chain([constant]).collect()
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    let mut row = 0;
+    for column in 0..columns {
+        let Some(pivot) = (row..rows).find(|&r| !matrix[r][column].is_zero()) else { continue };
+        matrix.swap(row, pivot);
+        let scale = matrix[row][column];
+        for value in matrix[row].iter_mut() {
+            *value = value.checked_div(scale)?;
+        }
+        let pivot_row = matrix[row].clone();
+        for (other, values) in matrix.iter_mut().enumerate() {
+            let factor = values[column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for (value, &pivot_value) in values[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value = value.checked_sub(factor.checked_mul(pivot_value)?)?;
+            }
+        }
+        pivots.push(column);
+        row += 1;
+    }
+
+    if matrix[row..].iter().any(|r| !r[columns].is_zero()) {
+        return Some(Solution::None);
+    }
+
+    let mut values = vec![Rational::ZERO; columns];
+    for (r, &column) in pivots.iter().enumerate() {
+        values[column] = matrix[r][columns];
+    }
+    if pivots.len() == columns {
+        Some(Solution::Unique(values))
+    } else {
+        let free = (0..columns).filter(|c| !pivots.contains(c)).collect();
+        Some(Solution::Infinite { particular: values, free })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod rational {
+        use crate::rational::Rational;
+
+        #[test]
+        fn normalised() {
+            assert_eq!(Rational::new(1, 2), Rational::new(-3, -6));
+            assert_eq!(Rational::new(-2, 3), Rational::new(4, -6));
+            assert_eq!("-2/3", Rational::new(4, -6).to_string());
+            assert_eq!("5", Rational::new(10, 2).to_string());
+        }
+
+        #[test]
+        fn arithmetic() {
+            let half = Rational::new(1, 2);
+            let third = Rational::new(1, 3);
+            assert_eq!(Rational::new(5, 6), half + third);
+            assert_eq!(Rational::new(1, 6), half - third);
+            assert_eq!(Rational::new(1, 6), half * third);
+            assert_eq!(Rational::new(3, 2), half / third);
+            assert!(third < half);
+            assert_eq!(None, Rational::ZERO.recip());
+        }
+
+        #[test]
+        fn overflow() {
+            let big = Rational::from(i128::MAX);
+            assert_eq!(None, big.checked_add(Rational::ONE));
+            assert_eq!(None, big.checked_mul(Rational::from(2i128)));
+            assert_eq!(Some(Rational::ONE), big.checked_div(big));
+            assert_eq!(None, Rational::checked_new(i128::MIN, 1));
+            assert_eq!(None, Rational::checked_new(0, i128::MIN));
+            assert_eq!(None, Rational::checked_new(i128::MIN, i128::MIN));
+        }
+
+        #[test]
+        fn ordering_near_the_limit() {
+            let max = i128::MAX;
+            assert!(Rational::new(max, 2) > Rational::new(-max, 3));
+            assert!(Rational::new(-max, 3) < Rational::new(max, 2));
+            // 1 + 1/(max - 1) against 1 + 1/(max - 2)
+            assert!(Rational::new(max, max - 1) < Rational::new(max - 1, max - 2));
+            assert!(Rational::new(max - 1, max - 2) > Rational::new(max, max - 1));
+            assert_eq!(Some(Rational::new(max, 7)), [Rational::new(-max, 5), Rational::new(max, 7)].into_iter().max());
+            assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        }
+    }
+
+    mod solve {
+        use crate::rational::{solve, Rational, Solution};
+
+        fn matrix(rows: &[&[i128]]) -> Vec<Vec<Rational>> {
+            rows.iter().map(|row| row.iter().map(|&v| Rational::from(v)).collect()).collect()
+        }
+
+        fn vector(values: &[i128]) -> Vec<Rational> {
+            values.iter().map(|&v| Rational::from(v)).collect()
+        }
+
+        #[test]
+        fn unique() {
+            let solution = solve(&matrix(&[&[2, 1, -1], &[-3, -1, 2], &[-2, 1, 2]]), &vector(&[8, -11, -3]));
+            assert_eq!(Some(vec![2, 3, -1]), solution.unwrap().integer_solution());
+        }
+
+        #[test]
+        fn fractional() {
+            let solution = solve(&matrix(&[&[2, 0], &[0, 3]]), &vector(&[1, 1])).unwrap();
+            assert_eq!(Solution::Unique(vec![Rational::new(1, 2), Rational::new(1, 3)]), solution);
+            assert_eq!(None, solution.integer_solution());
+        }
+
+        #[test]
+        fn infinite() {
+            let solution = solve(&matrix(&[&[1, 2], &[2, 4]]), &vector(&[3, 6])).unwrap();
+            assert_eq!(Solution::Infinite { particular: vector(&[3, 0]), free: vec![1] }, solution);
+        }
+
+        #[test]
+        fn inconsistent() {
+            assert_eq!(Some(Solution::None), solve(&matrix(&[&[1, 2], &[2, 4]]), &vector(&[3, 7])));
+        }
+
+        #[test]
+        fn overflow() {
+            let huge = i128::MAX / 2;
+            assert_eq!(None, solve(&matrix(&[&[1, huge], &[huge, 1]]), &vector(&[huge, 1])));
+        }
+    }
+}
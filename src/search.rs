@@ -0,0 +1,328 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+pub struct Search<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    distances: Vec<usize>,
+    predecessors: Vec<Vec<usize>>,
+    settled: Vec<bool>,
+    goal: Option<usize>,
+}
+
+pub fn bfs<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    Search::run(
+        starts,
+        |s| neighbours(s).into_iter().map(|next| (next, 1)),
+        |_| 0,
+        is_goal,
+    )
+}
+
+pub fn dijkstra<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    Search::run(starts, neighbours, |_| 0, is_goal)
+}
+
+// The heuristic must never overestimate the remaining cost, or the result may not be optimal
+pub fn astar<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: impl FnMut(&S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    Search::run(starts, neighbours, heuristic, is_goal)
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn run<N, I>(
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: N,
+        mut heuristic: impl FnMut(&S) -> usize,
+        mut is_goal: impl FnMut(&S) -> bool,
+    ) -> Self
+    where
+        N: FnMut(&S) -> I,
+        I: IntoIterator<Item = (S, usize)>,
+    {
+        let mut search = Search {
+            states: Vec::new(),
+            index: HashMap::new(),
+            distances: Vec::new(),
+            predecessors: Vec::new(),
+            settled: Vec::new(),
+            goal: None,
+        };
+        let mut frontier = BinaryHeap::new();
+        for start in starts {
+            let estimate = heuristic(&start);
+            let id = search.intern(start);
+            search.distances[id] = 0;
+            frontier.push(Reverse((estimate, 0, id)));
+        }
+
+        while let Some(Reverse((_, distance, id))) = frontier.pop() {
+            if search.settled[id] || distance > search.distances[id] {
+                continue;
+            }
+            search.settled[id] = true;
+            let state = search.states[id].clone();
+            if is_goal(&state) {
+                search.goal = Some(id);
+                break;
+            }
+            for (next, cost) in neighbours(&state) {
+                let next_distance = distance + cost;
+                let estimate = heuristic(&next);
+                let next_id = search.intern(next);
+                if next_distance < search.distances[next_id] {
+                    search.distances[next_id] = next_distance;
+                    search.predecessors[next_id] = vec![id];
+                    frontier.push(Reverse((next_distance + estimate, next_distance, next_id)));
+                } else if next_distance == search.distances[next_id]
+                    && !search.settled[next_id]
+                    && !search.predecessors[next_id].contains(&id)
+                {
+                    search.predecessors[next_id].push(id);
+                }
+            }
+        }
+
+        search
+    }
+
+    fn intern(&mut self, state: S) -> usize {
+        if let Some(&id) = self.index.get(&state) {
+            return id;
+        }
+        let id = self.states.len();
+        self.index.insert(state.clone(), id);
+        self.states.push(state);
+        self.distances.push(usize::MAX);
+        self.predecessors.push(Vec::new());
+        self.settled.push(false);
+        id
+    }
+
+    fn settled_id(&self, state: &S) -> Option<usize> {
+        self.index.get(state).copied().filter(|&id| self.settled[id])
+    }
+
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.map(|id| &self.states[id])
+    }
+
+    pub fn goal_distance(&self) -> Option<usize> {
+        self.goal.map(|id| self.distances[id])
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.settled_id(state).map(|id| self.distances[id])
+    }
+
+    pub fn visited(&self) -> impl Iterator<Item = &S> {
+        self.states
+            .iter()
+            .zip(self.settled.iter())
+            .filter(|(_, &settled)| settled)
+            .map(|(state, _)| state)
+    }
+
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        let mut id = self.settled_id(state)?;
+        let mut path = vec![self.states[id].clone()];
+        while let Some(&previous) = self.predecessors[id].first() {
+            path.push(self.states[previous].clone());
+            id = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn all_paths_to(&self, state: &S) -> Vec<Vec<S>> {
+        let Some(id) = self.settled_id(state) else { return Vec::new() };
+        let mut paths = Vec::new();
+        let mut pending = vec![vec![id]];
+        while let Some(partial) = pending.pop() {
+            let first = *partial.last().unwrap();
+            if self.predecessors[first].is_empty() {
+                paths.push(partial.iter().rev().map(|&i| self.states[i].clone()).collect());
+                continue;
+            }
+            for &previous in &self.predecessors[first] {
+                let mut extended = partial.clone();
+                extended.push(previous);
+                pending.push(extended);
+            }
+        }
+        paths
+    }
+
+    fn ancestors(&self, id: usize) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if seen.insert(id) {
+                pending.extend(self.predecessors[id].iter().copied());
+            }
+        }
+        seen
+    }
+
+    pub fn on_paths_to(&self, state: &S) -> HashSet<S> {
+        match self.settled_id(state) {
+            Some(id) => self.ancestors(id).into_iter().map(|id| self.states[id].clone()).collect(),
+            None => HashSet::new(),
+        }
+    }
+
+    pub fn count_paths_to(&self, state: &S) -> usize {
+        let Some(target) = self.settled_id(state) else { return 0 };
+        // distances tie along zero-cost edges, so count each node after all its predecessors
+        // by walking back from the target instead of sorting by distance
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        let mut pending = vec![target];
+        while let Some(&id) = pending.last() {
+            let missing: Vec<usize> = self.predecessors[id].iter()
+                .copied()
+                .filter(|p| !counts.contains_key(p))
+                .collect();
+            if !missing.is_empty() {
+                pending.extend(missing);
+                continue;
+            }
+            pending.pop();
+            let count = if self.predecessors[id].is_empty() {
+                1
+            } else {
+                self.predecessors[id].iter().map(|p| counts[p]).sum()
+            };
+            counts.insert(id, count);
+        }
+        counts[&target]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod bfs {
+        use crate::grid::Grid;
+        use crate::search::bfs;
+
+        const MAZE: &str = "\
+S..#
+.#.#
+...E";
+
+        #[test]
+        fn shortest_distance() {
+            let maze = Grid::parse(MAZE, |c| c);
+            let start = maze.find(|&c| c == 'S').unwrap();
+            let search = bfs(
+                [start],
+                |&cell| maze.neighbours4(cell).filter(|&n| maze[n] != '#').collect::<Vec<_>>(),
+                |&cell| maze[cell] == 'E',
+            );
+            assert_eq!(Some(&(2, 3)), search.goal());
+            assert_eq!(Some(5), search.goal_distance());
+            assert_eq!(6, search.path_to(&(2, 3)).unwrap().len());
+        }
+
+        #[test]
+        fn all_optimal_paths() {
+            let maze = Grid::parse(MAZE, |c| c);
+            let search = bfs(
+                [(0, 0)],
+                |&cell| maze.neighbours4(cell).filter(|&n| maze[n] != '#').collect::<Vec<_>>(),
+                |_| false,
+            );
+            assert_eq!(2, search.count_paths_to(&(2, 3)));
+            assert_eq!(2, search.all_paths_to(&(2, 3)).len());
+            assert_eq!(9, search.on_paths_to(&(2, 3)).len());
+            assert_eq!(9, search.visited().count());
+        }
+
+        #[test]
+        fn unreachable() {
+            let search = bfs([0], |&n: &usize| if n < 3 { vec![n + 1] } else { vec![] }, |&n| n == 5);
+            assert_eq!(None, search.goal());
+            assert_eq!(None, search.distance(&5));
+            assert_eq!(Some(3), search.distance(&3));
+        }
+    }
+
+    mod weighted {
+        use crate::search::{astar, dijkstra};
+
+        fn edges(node: &char) -> Vec<(char, usize)> {
+            match node {
+                'a' => vec![('b', 7), ('c', 9), ('f', 14)],
+                'b' => vec![('c', 10), ('d', 15)],
+                'c' => vec![('d', 11), ('f', 2)],
+                'd' => vec![('e', 6)],
+                'f' => vec![('e', 9)],
+                _ => vec![],
+            }
+        }
+
+        #[test]
+        fn dijkstra_path() {
+            let search = dijkstra(['a'], edges, |&n| n == 'e');
+            assert_eq!(Some(20), search.goal_distance());
+            assert_eq!(Some(vec!['a', 'c', 'f', 'e']), search.path_to(&'e'));
+        }
+
+        #[test]
+        fn zero_cost_cycle() {
+            // a and b are each other's neighbour for free, which must not make them each other's predecessor
+            let edges = |node: &char| match node {
+                'a' => vec![('b', 0)],
+                'b' => vec![('a', 0), ('c', 1)],
+                _ => vec![],
+            };
+            let search = dijkstra(['a'], edges, |_| false);
+            assert_eq!(Some(vec!['a', 'b', 'c']), search.path_to(&'c'));
+            assert_eq!(vec![vec!['a', 'b', 'c']], search.all_paths_to(&'c'));
+            assert_eq!(1, search.count_paths_to(&'c'));
+        }
+
+        #[test]
+        fn astar_grid() {
+            let goal = (20isize, 20isize);
+            let search = astar(
+                [(0isize, 0isize)],
+                |&(x, y)| [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)]
+                    .into_iter()
+                    .filter(|&(x, y)| x >= 0 && y >= 0 && x <= 20 && y <= 20 && !(x == 10 && y < 15))
+                    .map(|p| (p, 1))
+                    .collect::<Vec<_>>(),
+                |&(x, y)| (goal.0 - x).unsigned_abs() + (goal.1 - y).unsigned_abs(),
+                |&p| p == goal,
+            );
+            assert_eq!(Some(40), search.goal_distance());
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::ops::Index;
+use crate::coord::Point;
+use crate::grid::{Grid, Surface};
+
+// An unbounded grid that only stores the points it has been given. The bounds grow to cover
+// every insert and are not shrunk again by removals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid { cells: HashMap::new(), bounds: None }
+    }
+
+    // Keeps only the cells matching the predicate, e.g. the walls of a maze
+    pub fn from_grid(grid: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        grid.cells()
+            .filter(|(_, value)| predicate(value))
+            .map(|(cell, value)| (Point::from_cell(cell), value.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+        });
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn get_or_insert_with(&mut self, point: Point, f: impl FnOnce() -> T) -> &mut T {
+        if !self.contains(point) {
+            self.insert(point, f());
+        }
+        self.cells.get_mut(&point).unwrap()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    // Copies the bounded area into a dense grid, with the top-left corner at (0, 0)
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds else { return Grid::new(0, 0, fill) };
+        let mut grid = Grid::new((max.y - min.y + 1) as usize, (max.x - min.x + 1) as usize, fill);
+        for (&point, value) in &self.cells {
+            grid[point - (min - Point::ORIGIN)] = value.clone();
+        }
+        grid
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (point, value) in iter {
+            grid.insert(point, value);
+        }
+        grid
+    }
+}
+
+impl<T> Surface<T> for SparseGrid<T> {
+    fn at(&self, point: Point) -> Option<&T> {
+        self.get(point)
+    }
+
+    fn canonical(&self, point: Point) -> Option<Point> {
+        Some(point)
+    }
+
+    fn extent(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+}
+
+impl<T> Index<Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(value) => value,
+            None => panic!("{} is not set", point),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod sparse {
+        use crate::coord::Point;
+        use crate::grid::{Grid, Surface};
+        use crate::sparse_grid::SparseGrid;
+
+        #[test]
+        fn grows() {
+            let mut grid = SparseGrid::new();
+            grid.insert(Point::new(0, 0), '#');
+            grid.insert(Point::new(-2, 1), '#');
+            grid.insert(Point::new(1, -1), '#');
+            assert_eq!(Some((Point::new(-2, -1), Point::new(1, 1))), grid.bounds());
+            assert_eq!(None, grid.get(Point::new(5, 5)));
+            assert_eq!("...#\n..#.\n#...\n", grid.render_with(|c| *c.unwrap_or(&'.')));
+            *grid.get_or_insert_with(Point::new(3, 0), || '.') = '#';
+            assert_eq!(4, grid.len());
+            assert_eq!(8, grid.adjacent8(Point::new(-100, 100)).count());
+        }
+
+        #[test]
+        fn dense_round_trip() {
+            let dense = Grid::parse("#..\n.#.", |c| c);
+            let sparse = SparseGrid::from_grid(&dense, |&c| c == '#');
+            assert_eq!(2, sparse.len());
+            assert_eq!('#', sparse[Point::new(1, 1)]);
+            assert_eq!(Grid::parse("#.\n.#", |c| c), sparse.to_grid('.'));
+        }
+    }
+}
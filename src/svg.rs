@@ -0,0 +1,137 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::coord::Point;
+
+// Drawing happens in cell units: a Point names a cell and shapes pass through cell centres,
+// except `edge`, whose points are the corners between cells
+pub struct Svg {
+    width: usize,
+    height: usize,
+    scale: usize,
+    elements: Vec<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl Svg {
+    pub fn new(width: usize, height: usize, scale: usize) -> Self {
+        Svg { width, height, scale, elements: Vec::new() }
+    }
+
+    fn centre(&self, p: Point) -> (f64, f64) {
+        let scale = self.scale as f64;
+        ((p.x as f64 + 0.5) * scale, (p.y as f64 + 0.5) * scale)
+    }
+
+    fn points_attribute(&self, points: &[Point]) -> String {
+        points.iter()
+            .map(|&p| {
+                let (x, y) = self.centre(p);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn cell(&mut self, p: Point, fill: &str) {
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            p.x * self.scale as isize, p.y * self.scale as isize, self.scale, self.scale, escape(fill),
+        ));
+    }
+
+    pub fn cells(&mut self, cells: impl IntoIterator<Item = Point>, fill: &str) {
+        for p in cells {
+            self.cell(p, fill);
+        }
+    }
+
+    pub fn polyline(&mut self, points: &[Point], stroke: &str, stroke_width: f64) {
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+            self.points_attribute(points), escape(stroke), stroke_width,
+        ));
+    }
+
+    pub fn polygon(&mut self, points: &[Point], fill: &str, stroke: &str) {
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="{}" stroke="{}"/>"#,
+            self.points_attribute(points), escape(fill), escape(stroke),
+        ));
+    }
+
+    pub fn edge(&mut self, from: Point, to: Point, stroke: &str, stroke_width: f64) {
+        let scale = self.scale as isize;
+        self.elements.push(format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+            from.x * scale, from.y * scale, to.x * scale, to.y * scale, escape(stroke), stroke_width,
+        ));
+    }
+
+    pub fn label(&mut self, p: Point, text: &str, colour: &str) {
+        let (x, y) = self.centre(p);
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" font-size="{}" font-family="monospace" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+            x, y, self.scale as f64 * 0.8, escape(colour), escape(text),
+        ));
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = (self.width * self.scale, self.height * self.scale);
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            width, height, width, height,
+        )?;
+        writeln!(f, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+        for element in &self.elements {
+            writeln!(f, "{}", element)?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod svg {
+        use crate::coord::Point;
+        use crate::svg::Svg;
+
+        #[test]
+        fn document() {
+            let svg = Svg::new(3, 2, 10);
+            let text = svg.to_string();
+            assert!(text.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 30 20">"#));
+            assert!(text.ends_with("</svg>\n"));
+        }
+
+        #[test]
+        fn shapes_are_scaled() {
+            let mut svg = Svg::new(3, 3, 10);
+            svg.cell(Point::new(1, 2), "red");
+            svg.polyline(&[Point::new(0, 0), Point::new(2, 0)], "blue", 2.0);
+            svg.edge(Point::new(1, 1), Point::new(1, 3), "black", 1.5);
+            let text = svg.to_string();
+            assert!(text.contains(r#"<rect x="10" y="20" width="10" height="10" fill="red"/>"#));
+            assert!(text.contains(r#"points="5,5 25,5""#));
+            assert!(text.contains(r#"<line x1="10" y1="10" x2="10" y2="30""#));
+        }
+
+        #[test]
+        fn labels_are_escaped() {
+            let mut svg = Svg::new(1, 1, 10);
+            svg.label(Point::new(0, 0), "<&>", "black");
+            assert!(svg.to_string().contains(">&lt;&amp;&gt;</text>"));
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::{Index, IndexMut};
+use crate::coord::Point;
+use crate::grid::{Cell, Grid, Surface};
+
+// A grid whose edges join up, so any point names a cell: stepping off one side comes back in
+// on the other. Rendering shows the single tile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TorusGrid<T> {
+    grid: Grid<T>,
+}
+
+impl<T> TorusGrid<T> {
+    pub fn new(height: usize, width: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        TorusGrid::from_grid(Grid::new(height, width, fill))
+    }
+
+    pub fn from_grid(grid: Grid<T>) -> Self {
+        assert!(grid.height() > 0 && grid.width() > 0, "A torus needs at least one cell");
+        TorusGrid { grid }
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    // Euclidean modulo on both axes, so the result is always inside the tile
+    pub fn wrap(&self, point: Point) -> Point {
+        Point::new(point.x.rem_euclid(self.width() as isize), point.y.rem_euclid(self.height() as isize))
+    }
+
+    pub fn cell(&self, point: Point) -> Cell {
+        self.wrap(point).to_cell().unwrap()
+    }
+
+    pub fn get(&self, point: Point) -> &T {
+        &self.grid[self.cell(point)]
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> &mut T {
+        let cell = self.cell(point);
+        &mut self.grid[cell]
+    }
+
+    pub fn set(&mut self, point: Point, value: T) {
+        *self.get_mut(point) = value;
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.grid.cells().map(|(cell, value)| (Point::from_cell(cell), value))
+    }
+}
+
+impl<T> Surface<T> for TorusGrid<T> {
+    fn at(&self, point: Point) -> Option<&T> {
+        Some(self.get(point))
+    }
+
+    fn canonical(&self, point: Point) -> Option<Point> {
+        Some(self.wrap(point))
+    }
+
+    fn extent(&self) -> Option<(Point, Point)> {
+        self.grid.extent()
+    }
+}
+
+impl<T> Index<Point> for TorusGrid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+    }
+}
+
+impl<T> IndexMut<Point> for TorusGrid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod torus {
+        use crate::coord::Point;
+        use crate::grid::{Grid, Surface};
+        use crate::torus::TorusGrid;
+
+        #[test]
+        fn wraps() {
+            let torus = TorusGrid::from_grid(Grid::parse("ab\ncd\nef", |c| c));
+            assert_eq!(Point::new(1, 2), torus.wrap(Point::new(-1, -1)));
+            assert_eq!(Point::new(0, 0), torus.wrap(Point::new(4, 9)));
+            assert_eq!('f', torus[Point::new(-3, 5)]);
+            assert_eq!((2, 1), torus.cell(Point::new(-1, -1)));
+            assert_eq!("ab\ncd\nef\n", torus.render_with(|c| *c.unwrap()));
+        }
+
+        #[test]
+        fn neighbours() {
+            let torus = TorusGrid::new(3, 3, 0);
+            let mut around = torus.adjacent4(Point::ORIGIN).collect::<Vec<_>>();
+            around.sort();
+            assert_eq!(vec![Point::new(0, 1), Point::new(0, 2), Point::new(1, 0), Point::new(2, 0)], around);
+            assert_eq!(8, torus.adjacent8(Point::ORIGIN).count());
+        }
+
+        #[test]
+        fn counts() {
+            let mut torus = TorusGrid::new(2, 2, 0);
+            for x in -3..5 {
+                torus[Point::new(x, x)] += 1;
+            }
+            assert_eq!(vec![4, 0, 0, 4], torus.cells().map(|(_, &n)| n).collect::<Vec<_>>());
+        }
+    }
+}
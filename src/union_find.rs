@@ -0,0 +1,139 @@
+// Disjoint sets over 0..n. Alongside the parent links, each set's members form a circular
+// list through `next`, so a component can be listed without scanning every element.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    next: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            next: (0..n).collect(),
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, mut x: usize) -> usize {
+        // path halving: point every other node on the way at its grandparent
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    // Returns false when the two were already in the same component
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.next.swap(a, b);
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    // Everything in the same component as x, starting with x
+    pub fn members(&self, x: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut current = Some(x);
+        std::iter::from_fn(move || {
+            let member = current?;
+            let next = self.next[member];
+            current = (next != x).then_some(next);
+            Some(member)
+        })
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = impl Iterator<Item = usize> + '_> + '_ {
+        (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.members(root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod union_find {
+        use crate::union_find::UnionFind;
+
+        #[test]
+        fn merging() {
+            let mut sets = UnionFind::new(6);
+            assert_eq!(6, sets.component_count());
+            assert!(sets.union(0, 1));
+            assert!(sets.union(2, 3));
+            assert!(sets.union(1, 3));
+            assert!(!sets.union(0, 2));
+            assert_eq!(3, sets.component_count());
+            assert!(sets.connected(0, 3));
+            assert!(!sets.connected(0, 4));
+            assert_eq!(4, sets.component_size(2));
+            assert_eq!(1, sets.component_size(5));
+        }
+
+        #[test]
+        fn members() {
+            let mut sets = UnionFind::new(7);
+            for (a, b) in [(0, 4), (4, 6), (1, 5)] {
+                sets.union(a, b);
+            }
+            let mut members = sets.members(6).collect::<Vec<_>>();
+            assert_eq!(6, members[0]);
+            members.sort();
+            assert_eq!(vec![0, 4, 6], members);
+
+            let mut components = sets.components()
+                .map(|c| {
+                    let mut c = c.collect::<Vec<_>>();
+                    c.sort();
+                    c
+                })
+                .collect::<Vec<_>>();
+            components.sort();
+            assert_eq!(vec![vec![0, 4, 6], vec![1, 5], vec![2], vec![3]], components);
+        }
+
+        #[test]
+        fn long_chain() {
+            let n = 100_000;
+            let mut sets = UnionFind::new(n);
+            for i in 1..n {
+                sets.union(i - 1, i);
+            }
+            assert_eq!(1, sets.component_count());
+            assert_eq!(n, sets.component_size(0));
+            assert_eq!(n, sets.members(n / 2).count());
+        }
+    }
+}
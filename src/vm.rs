@@ -0,0 +1,437 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use crate::parse::ParseError;
+
+pub type Word = i64;
+pub type Register = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Immediate(Word),
+}
+
+// Jumps are relative to the jumping instruction, so "jmp -1" loops forever on the one before
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Xor(Register, Operand),
+    Load(Register, Operand),
+    Store(Operand, Operand),
+    Jump(isize),
+    JumpIfZero(Operand, isize),
+    JumpIfNotZero(Operand, isize),
+    Out(Operand),
+    Halt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    NoSuchRegister(Register),
+    NoSuchAddress(Word),
+    DivisionByZero,
+    Overflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(usize),
+    StepLimit,
+    Fault { address: usize, fault: Fault },
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(r) => write!(f, "r{}", r),
+            Operand::Immediate(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Instruction {
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Set(..) => "set",
+            Instruction::Add(..) => "add",
+            Instruction::Mul(..) => "mul",
+            Instruction::Div(..) => "div",
+            Instruction::Mod(..) => "mod",
+            Instruction::Xor(..) => "xor",
+            Instruction::Load(..) => "load",
+            Instruction::Store(..) => "store",
+            Instruction::Jump(..) => "jmp",
+            Instruction::JumpIfZero(..) => "jz",
+            Instruction::JumpIfNotZero(..) => "jnz",
+            Instruction::Out(..) => "out",
+            Instruction::Halt => "halt",
+        }
+    }
+
+    fn jump_offset(&self) -> Option<isize> {
+        match *self {
+            Instruction::Jump(offset) | Instruction::JumpIfZero(_, offset) | Instruction::JumpIfNotZero(_, offset) => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mnemonic())?;
+        match self {
+            Instruction::Set(r, v) | Instruction::Add(r, v) | Instruction::Mul(r, v) | Instruction::Div(r, v)
+            | Instruction::Mod(r, v) | Instruction::Xor(r, v) | Instruction::Load(r, v) => write!(f, " r{}, {}", r, v),
+            Instruction::Store(address, v) => write!(f, " {}, {}", address, v),
+            Instruction::Jump(offset) => write!(f, " {}", offset),
+            Instruction::JumpIfZero(v, offset) | Instruction::JumpIfNotZero(v, offset) => write!(f, " {}, {}", v, offset),
+            Instruction::Out(v) => write!(f, " {}", v),
+            Instruction::Halt => Ok(()),
+        }
+    }
+}
+
+fn operand((text, column): (&str, usize)) -> Result<Operand, ParseError> {
+    match text.strip_prefix('r') {
+        Some(r) => r.parse().map(Operand::Register),
+        None => text.parse().map(Operand::Immediate),
+    }.map_err(|_| ParseError::new(column, format!("expected a register or a number, found {:?}", text)))
+}
+
+fn register(field: (&str, usize)) -> Result<Register, ParseError> {
+    match operand(field)? {
+        Operand::Register(r) => Ok(r),
+        Operand::Immediate(_) => Err(ParseError::new(field.1, format!("expected a register, found {:?}", field.0))),
+    }
+}
+
+fn offset((text, column): (&str, usize)) -> Result<isize, ParseError> {
+    text.parse().map_err(|_| ParseError::new(column, format!("expected a jump offset, found {:?}", text)))
+}
+
+// One instruction in the same syntax the disassembler prints, e.g. "add r1, r2"
+pub fn decode(line: &str) -> Result<Instruction, ParseError> {
+    let trimmed = line.trim_start();
+    let start = line.len() - trimmed.len() + 1;
+    let (mnemonic, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+    let mut arguments = Vec::new();
+    let mut column = start + mnemonic.len() + 1;
+    if !rest.trim().is_empty() {
+        for part in rest.split(',') {
+            let text = part.trim();
+            arguments.push((text, column + part.len() - part.trim_start().len()));
+            column += part.len() + 1;
+        }
+    }
+    let expect = |count: usize| {
+        if arguments.len() == count {
+            Ok(())
+        } else {
+            Err(ParseError::new(start, format!("{} takes {} arguments, found {}", mnemonic, count, arguments.len())))
+        }
+    };
+    let instruction = match mnemonic {
+        "set" | "add" | "mul" | "div" | "mod" | "xor" | "load" => {
+            expect(2)?;
+            let (r, v) = (register(arguments[0])?, operand(arguments[1])?);
+            match mnemonic {
+                "set" => Instruction::Set(r, v),
+                "add" => Instruction::Add(r, v),
+                "mul" => Instruction::Mul(r, v),
+                "div" => Instruction::Div(r, v),
+                "mod" => Instruction::Mod(r, v),
+                "xor" => Instruction::Xor(r, v),
+                _ => Instruction::Load(r, v),
+            }
+        }
+        "store" => {
+            expect(2)?;
+            Instruction::Store(operand(arguments[0])?, operand(arguments[1])?)
+        }
+        "jmp" => {
+            expect(1)?;
+            Instruction::Jump(offset(arguments[0])?)
+        }
+        "jz" | "jnz" => {
+            expect(2)?;
+            let (v, jump) = (operand(arguments[0])?, offset(arguments[1])?);
+            if mnemonic == "jz" { Instruction::JumpIfZero(v, jump) } else { Instruction::JumpIfNotZero(v, jump) }
+        }
+        "out" => {
+            expect(1)?;
+            Instruction::Out(operand(arguments[0])?)
+        }
+        "halt" => {
+            expect(0)?;
+            Instruction::Halt
+        }
+        _ => return Err(ParseError::new(start, format!("unknown instruction {:?}", mnemonic))),
+    };
+    Ok(instruction)
+}
+
+// A listing with addresses and resolved jump targets; anything after ';' is a comment, so
+// the listing decodes back to the same program once the addresses are stripped
+pub fn disassemble(program: &[Instruction]) -> String {
+    let width = program.len().saturating_sub(1).to_string().len();
+    let mut listing = String::new();
+    for (address, instruction) in program.iter().enumerate() {
+        let text = instruction.to_string();
+        match instruction.jump_offset() {
+            Some(offset) => listing.push_str(&format!(
+                "{:>width$}  {:<16}; -> {}\n", address, text, address as isize + offset, width = width
+            )),
+            None => listing.push_str(&format!("{:>width$}  {}\n", address, text, width = width)),
+        }
+    }
+    listing
+}
+
+pub struct Vm {
+    program: Vec<Instruction>,
+    registers: Vec<Word>,
+    memory: Vec<Word>,
+    pc: usize,
+    steps: usize,
+    output: Vec<Word>,
+    breakpoints: HashSet<usize>,
+}
+
+impl Vm {
+    pub fn new(program: Vec<Instruction>, registers: usize) -> Self {
+        Vm {
+            program,
+            registers: vec![0; registers],
+            memory: Vec::new(),
+            pc: 0,
+            steps: 0,
+            output: Vec::new(),
+            breakpoints: HashSet::new(),
+        }
+    }
+
+    pub fn with_memory(self, memory: Vec<Word>) -> Self {
+        Vm { memory, ..self }
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    pub fn registers(&self) -> &[Word] {
+        &self.registers
+    }
+
+    pub fn register(&self, r: Register) -> Word {
+        self.registers[r]
+    }
+
+    pub fn set_register(&mut self, r: Register, value: Word) {
+        self.registers[r] = value;
+    }
+
+    pub fn memory(&self) -> &[Word] {
+        &self.memory
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn output(&self) -> &[Word] {
+        &self.output
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) {
+        self.breakpoints.remove(&address);
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.program.get(self.pc).is_none_or(|&i| i == Instruction::Halt)
+    }
+
+    fn read(&self, operand: Operand) -> Result<Word, Fault> {
+        match operand {
+            Operand::Register(r) => self.registers.get(r).copied().ok_or(Fault::NoSuchRegister(r)),
+            Operand::Immediate(value) => Ok(value),
+        }
+    }
+
+    fn write(&mut self, r: Register, value: Word) -> Result<(), Fault> {
+        *self.registers.get_mut(r).ok_or(Fault::NoSuchRegister(r))? = value;
+        Ok(())
+    }
+
+    fn address(&self, operand: Operand) -> Result<usize, Fault> {
+        let address = self.read(operand)?;
+        usize::try_from(address).ok()
+            .filter(|&a| a < self.memory.len())
+            .ok_or(Fault::NoSuchAddress(address))
+    }
+
+    fn arithmetic(&mut self, r: Register, v: Operand, f: fn(Word, Word) -> Option<Word>) -> Result<(), Fault> {
+        let (a, b) = (self.read(Operand::Register(r))?, self.read(v)?);
+        self.write(r, f(a, b).ok_or(Fault::Overflow)?)
+    }
+
+    fn divide(&mut self, r: Register, v: Operand, f: fn(Word, Word) -> Option<Word>) -> Result<(), Fault> {
+        if self.read(v)? == 0 {
+            return Err(Fault::DivisionByZero);
+        }
+        self.arithmetic(r, v, f)
+    }
+
+    // Executes one instruction; false once the machine has halted
+    pub fn step(&mut self) -> Result<bool, Fault> {
+        let Some(&instruction) = self.program.get(self.pc) else { return Ok(false) };
+        let mut next = self.pc as isize + 1;
+        match instruction {
+            Instruction::Set(r, v) => {
+                let value = self.read(v)?;
+                self.write(r, value)?;
+            }
+            Instruction::Add(r, v) => self.arithmetic(r, v, Word::checked_add)?,
+            Instruction::Mul(r, v) => self.arithmetic(r, v, Word::checked_mul)?,
+            Instruction::Div(r, v) => self.divide(r, v, Word::checked_div)?,
+            Instruction::Mod(r, v) => self.divide(r, v, Word::checked_rem_euclid)?,
+            Instruction::Xor(r, v) => self.arithmetic(r, v, |a, b| Some(a ^ b))?,
+            Instruction::Load(r, address) => {
+                let value = self.memory[self.address(address)?];
+                self.write(r, value)?;
+            }
+            Instruction::Store(address, v) => {
+                let (address, value) = (self.address(address)?, self.read(v)?);
+                self.memory[address] = value;
+            }
+            Instruction::Jump(offset) => next = self.pc as isize + offset,
+            Instruction::JumpIfZero(v, offset) => {
+                if self.read(v)? == 0 {
+                    next = self.pc as isize + offset;
+                }
+            }
+            Instruction::JumpIfNotZero(v, offset) => {
+                if self.read(v)? != 0 {
+                    next = self.pc as isize + offset;
+                }
+            }
+            Instruction::Out(v) => {
+                let value = self.read(v)?;
+                self.output.push(value);
+            }
+            Instruction::Halt => return Ok(false),
+        }
+        self.steps += 1;
+        // jumping before the start halts just like running off the end
+        self.pc = usize::try_from(next).unwrap_or(usize::MAX);
+        Ok(true)
+    }
+
+    // Runs until the program halts or faults, a breakpoint is reached, or the step limit is
+    // used up. A breakpoint on the current instruction is ignored, so run can resume from one.
+    pub fn run(&mut self, limit: Option<usize>) -> Stop {
+        let mut taken = 0;
+        loop {
+            if self.is_halted() {
+                return Stop::Halted;
+            }
+            if taken > 0 && self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+            if limit.is_some_and(|limit| taken >= limit) {
+                return Stop::StepLimit;
+            }
+            let address = self.pc;
+            if let Err(fault) = self.step() {
+                return Stop::Fault { address, fault };
+            }
+            taken += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod assembly {
+        use crate::vm::{decode, disassemble, Instruction, Operand};
+
+        #[test]
+        fn round_trip() {
+            let source = ["set r0, 5", "mul r1, r0", "store 3, -2", "jnz r0, -2", "jmp 4", "out r1", "halt"];
+            let program: Vec<Instruction> = source.iter().map(|line| decode(line).unwrap()).collect();
+            assert_eq!(Instruction::Store(Operand::Immediate(3), Operand::Immediate(-2)), program[2]);
+            let listing = disassemble(&program);
+            assert_eq!("3  jnz r0, -2      ; -> 1", listing.lines().nth(3).unwrap());
+            let decoded: Vec<Instruction> = listing.lines()
+                .map(|line| decode(line.split(';').next().unwrap().trim_start().split_once("  ").unwrap().1).unwrap())
+                .collect();
+            assert_eq!(program, decoded);
+        }
+
+        #[test]
+        fn errors() {
+            assert_eq!(1, decode("nop").unwrap_err().column);
+            assert_eq!(5, decode("add 3, r1").unwrap_err().column);
+            assert_eq!(9, decode("add r1, x").unwrap_err().column);
+            assert!(decode("halt r0").is_err());
+        }
+    }
+
+    mod execution {
+        use crate::vm::{decode, Fault, Instruction, Stop, Vm};
+
+        fn assemble(source: &str) -> Vec<Instruction> {
+            source.lines().map(|line| decode(line).unwrap()).collect()
+        }
+
+        // r1 = 10!, counting r0 down from 10
+        const FACTORIAL: &str = "set r0, 10\nset r1, 1\nmul r1, r0\nadd r0, -1\njnz r0, -2\nout r1";
+
+        #[test]
+        fn runs() {
+            let mut vm = Vm::new(assemble(FACTORIAL), 2);
+            assert_eq!(Stop::Halted, vm.run(None));
+            assert_eq!(3628800, vm.register(1));
+            assert_eq!(&[3628800], vm.output());
+            assert_eq!(33, vm.steps());
+        }
+
+        #[test]
+        fn limits_and_breakpoints() {
+            let mut vm = Vm::new(assemble(FACTORIAL), 2);
+            assert_eq!(Stop::StepLimit, vm.run(Some(5)));
+            assert_eq!(9, vm.register(0));
+            vm.add_breakpoint(4);
+            assert_eq!(Stop::Breakpoint(4), vm.run(None));
+            assert_eq!(Stop::Breakpoint(4), vm.run(None));
+            assert_eq!(7, vm.register(0));
+            vm.remove_breakpoint(4);
+            assert_eq!(Stop::Halted, vm.run(None));
+        }
+
+        #[test]
+        fn memory_and_faults() {
+            let mut vm = Vm::new(assemble("store 1, 42\nload r0, 1\nload r0, 5"), 1).with_memory(vec![0; 2]);
+            assert_eq!(Stop::Fault { address: 2, fault: Fault::NoSuchAddress(5) }, vm.run(None));
+            assert_eq!(&[0, 42], vm.memory());
+            assert_eq!(42, vm.register(0));
+
+            let mut vm = Vm::new(assemble("div r0, 0"), 1);
+            assert_eq!(Stop::Fault { address: 0, fault: Fault::DivisionByZero }, vm.run(None));
+            let mut vm = Vm::new(assemble("set r3, 1"), 1);
+            assert_eq!(Stop::Fault { address: 0, fault: Fault::NoSuchRegister(3) }, vm.run(None));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod year2023day11 {
-    use crate::read_lines;
+    use crate::combinatorics::pairs;
+    use crate::grid::Grid;
 
     type Coord = (usize, usize);
     struct GalaxyMap {
@@ -21,54 +22,38 @@ mod year2023day11 {
                 }
             }
 
-            let map: Vec<Vec<Space>> = read_lines(filename)
-                .map(|line| {
-                    let line_string = line.unwrap();
-                    line_string.chars().map(|c| char_to_space(c)).collect()
-                }).collect();
-            let empty_rows: Vec<usize> = map.iter().enumerate().filter_map(|(i, row)| {
+            let map: Grid<Space> = Grid::read(filename, char_to_space);
+            let empty_rows: Vec<usize> = map.rows().enumerate().filter_map(|(i, row)| {
                 if row.iter().all(|s| *s == Space::Empty) {
                     Some(i)
                 } else {
                     None
                 }
             }).collect();
-            let mut empty_cols: Vec<usize> = Vec::new();
-            for i in 0..map.first().unwrap().len() {
-                if map.iter().all(|row| row[i] == Space::Empty) {
-                    empty_cols.push(i);
+            let empty_cols: Vec<usize> = map.columns().enumerate().filter_map(|(i, mut col)| {
+                if col.all(|s| *s == Space::Empty) {
+                    Some(i)
+                } else {
+                    None
                 }
-            }
+            }).collect();
 
             let mut galaxies = Vec::new();
-            for (r, row) in map.iter().enumerate() {
-                for(c, space) in row.iter().enumerate() {
-                    if *space == Space::Empty {
-                        continue;
-                    }
-                    let c_offset = empty_cols.iter().filter(|&&e| e < c).count()
-                        * distance_multiplier;
-                    let r_offset = empty_rows.iter().filter(|&&e| e < r).count()
-                        * distance_multiplier;
-                    galaxies.push((r + r_offset, c + c_offset));
-                }
+            for (r, c) in map.find_all(|space| *space == Space::Galaxy) {
+                let c_offset = empty_cols.iter().filter(|&&e| e < c).count()
+                    * distance_multiplier;
+                let r_offset = empty_rows.iter().filter(|&&e| e < r).count()
+                    * distance_multiplier;
+                galaxies.push((r + r_offset, c + c_offset));
             }
 
             GalaxyMap { galaxies }
         }
         
         fn sum_distances(&self) -> usize {
-            let mut sum = 0;
-            
-            for a in 0..self.galaxies.len() {
-                for b in a+1..self.galaxies.len() {
-                    let ga = self.galaxies[a];
-                    let gb = self.galaxies[b];
-                    sum += ga.1.abs_diff(gb.1) + ga.0.abs_diff(gb.0);
-                }
-            }
-            
-            sum
+            pairs(&self.galaxies)
+                .map(|(ga, gb)| ga.1.abs_diff(gb.1) + ga.0.abs_diff(gb.0))
+                .sum()
         }
     }
 
@@ -1,7 +1,8 @@
 mod year2023day12 {
     use std::sync::mpsc;
     use std::thread;
-    use regex::Regex;
+    use crate::memo::Memo;
+    use crate::parse::{integers, scan, ParseError};
     use crate::read_lines;
 
     struct Row {
@@ -10,61 +11,40 @@ mod year2023day12 {
     }
 
     impl Row {
-        fn parse(line: &str) -> Self {
-            let pattern = Regex::new(r"([.#?]+) ([\d,]+)").unwrap();
-            let captures = pattern.captures(line).unwrap();
-            let notes = captures[1].to_string();
-            let rules = captures[2]
-                .split(",")
-                .map(|r| r.parse().unwrap())
-                .collect();
-
-            Self { notes, rules }
+        fn parse(line: &str) -> Result<Self, ParseError> {
+            let (notes, rules) = scan!("{} {}", line => String, String)?;
+            Ok(Self { notes, rules: integers(&rules)? })
         }
 
         fn count_valid_arrangements(&self) -> usize {
-            let mut count = 0;
-            let count_unknown = self.notes.chars()
-                .filter(|c| *c == '?' )
-                .count();
-
-            let conditions_for_index = |index: usize| -> Vec<String> {
-                let mut conditions = Vec::new();
-                for i in 0..count_unknown {
-                    let c = match (index / 2usize.pow(i as u32)) % 2 {
-                        0 => ".".to_string(),
-                        1 => "#".to_string(),
-                        _ => unreachable!()
-                    };
-                    conditions.push(c);
-                }
-                conditions
-            };
+            let mut memo = Memo::new();
+            let count = arrangements(&mut memo, self.notes.as_bytes(), &self.rules);
+            memo.trace(&self.notes);
+            count
+        }
+    }
 
-            let mut pattern_string = r"^\.*".to_string();
-            for (i, r) in self.rules.iter().enumerate() {
-                pattern_string.push_str(format!("#{{{}}}\\.", r).as_str());
-                if i < self.rules.len() - 1 {
-                    pattern_string.push_str("+");
-                } else {
-                    pattern_string.push_str("*$");
-                }
+    // Only the unplaced suffixes matter, and those are identified by their lengths
+    fn arrangements(memo: &mut Memo<(usize, usize), usize>, notes: &[u8], rules: &[usize]) -> usize {
+        let Some((&run, remaining_rules)) = rules.split_first() else {
+            return if notes.contains(&b'#') { 0 } else { 1 };
+        };
+        if notes.is_empty() {
+            return 0;
+        }
+        memo.get_or_insert_with((notes.len(), rules.len()), |memo| {
+            let mut count = 0;
+            if notes[0] != b'#' {
+                count += arrangements(memo, &notes[1..], rules);
             }
-            let pattern = Regex::new(&pattern_string).unwrap();
-
-            for i in 0..2usize.pow(count_unknown as u32) {
-                let add_ins = conditions_for_index(i);
-                let mut notes = self.notes.clone();
-                for j in 0..count_unknown {
-                    notes = notes.replacen('?', add_ins[j].as_str(), 1);
-                }
-                if pattern.is_match(&notes) {
-                    count += 1;
-                }
+            let fits = notes.len() >= run
+                && !notes[..run].contains(&b'.')
+                && notes.get(run) != Some(&b'#');
+            if notes[0] != b'.' && fits {
+                count += arrangements(memo, &notes[(run + 1).min(notes.len())..], remaining_rules);
             }
-
             count
-        }
+        })
     }
     
     fn part1(filename: &str) -> usize {
@@ -75,7 +55,7 @@ mod year2023day12 {
         while let Some(Ok(line)) = lines.next() {
             let tx1 = tx.clone();
             let _ = thread::spawn(move || {
-                let row = Row::parse(line.as_str());
+                let row = Row::parse(line.as_str()).unwrap();
                 tx1.send(row.count_valid_arrangements())
             });
         }
@@ -95,12 +75,12 @@ mod year2023day12 {
 
             #[test]
             fn single() {
-                assert_eq!(1, Row::parse("???.### 1,1,3").count_valid_arrangements());
-                assert_eq!(4, Row::parse(".??..??...?##. 1,1,3").count_valid_arrangements());
-                assert_eq!(1, Row::parse("?#?#?#?#?#?#?#? 1,3,1,6").count_valid_arrangements());
-                assert_eq!(1, Row::parse("????.#...#... 4,1,1").count_valid_arrangements());
-                assert_eq!(4, Row::parse("????.######..#####. 1,6,5").count_valid_arrangements());
-                assert_eq!(10, Row::parse("?###???????? 3,2,1").count_valid_arrangements());
+                assert_eq!(1, Row::parse("???.### 1,1,3").unwrap().count_valid_arrangements());
+                assert_eq!(4, Row::parse(".??..??...?##. 1,1,3").unwrap().count_valid_arrangements());
+                assert_eq!(1, Row::parse("?#?#?#?#?#?#?#? 1,3,1,6").unwrap().count_valid_arrangements());
+                assert_eq!(1, Row::parse("????.#...#... 4,1,1").unwrap().count_valid_arrangements());
+                assert_eq!(4, Row::parse("????.######..#####. 1,6,5").unwrap().count_valid_arrangements());
+                assert_eq!(10, Row::parse("?###???????? 3,2,1").unwrap().count_valid_arrangements());
             }
             
             #[test]
@@ -1,5 +1,6 @@
 mod year2023_day2 {
     use std::cmp::max;
+    use crate::parse::scan;
 
     #[derive(Debug)]
     #[derive(PartialEq)]
@@ -10,20 +11,8 @@ mod year2023_day2 {
 
     impl Game {
         fn parse(s: &str) -> Game {
-            let mut input = s.to_string();
-            input.drain(..5);
-            let colon_i = input.find(':').unwrap();
-            let id: usize = input.drain(..colon_i).as_str().parse().unwrap();
-            input.drain(..2);
-            let mut hands: Vec<Hand> = vec![];
-            while !input.is_empty() {
-                let end_i = match input.find(';') {
-                    Some(i) => i + 2,
-                    None => input.len(),
-                };
-                let hand = Hand::parse(input.drain(..end_i).as_str());
-                hands.push(hand);
-            }
+            let (id, hands) = scan!("Game {}: {}", s => usize, String).unwrap();
+            let hands = hands.split_inclusive(';').map(Hand::parse).collect();
 
             Game { id, hands }
         }
@@ -52,28 +41,15 @@ mod year2023_day2 {
 
     impl Hand {
         fn parse(s: &str) -> Hand {
-            let mut input = s.to_string();
             let mut blue = 0usize;
             let mut red = 0usize;
             let mut green = 0usize;
-            while !input.is_empty() {
-                let space_i = input.find(' ').unwrap();
-                let count: usize = input.drain(..space_i).as_str().parse().unwrap();
-                input.drain(..1);
-                let punc_i = input.find(|c: char| { c == ',' || c == ';' }).unwrap_or_else(|| input.len());
-                match input.drain(..punc_i).as_str() {
-                    "blue" => blue += count,
-                    "red" => red += count,
-                    "green" => green += count,
-                    c => panic!("Bad Color match: {}", c)
-                }
-                match input.find(' ') {
-                    Some(i) => {
-                        input.drain(..=i);
-                    }
-                    None => {
-                        input.drain(..input.len());
-                    }
+            for draw in s.trim().trim_end_matches(';').split(", ") {
+                match scan!("{} {}", draw => usize, String).unwrap() {
+                    (count, c) if c == "blue" => blue += count,
+                    (count, c) if c == "red" => red += count,
+                    (count, c) if c == "green" => green += count,
+                    (_, c) => panic!("Bad Color match: {}", c)
                 }
             }
             Hand { blue, red, green }
@@ -1,33 +1,23 @@
 mod year2023day5 {
-    use std::ops::Range;
+    use crate::interval::{IntervalSet, Piece, RangeMap};
     use crate::read_lines;
 
-    #[derive(PartialEq)]
-    #[derive(Debug)]
-    #[derive(Clone)]
-    struct RangeDelta {
-        in_range: Range<isize>,
-        delta: isize,
-    }
+    fn parse_piece(l: &str) -> Piece<usize> {
+        let mut iter = l.split_whitespace();
+        let destination = iter.next().unwrap().parse::<usize>().unwrap();
+        let source_start = iter.next().unwrap().parse::<usize>().unwrap();
+        let length = iter.next().unwrap().parse::<usize>().unwrap();
 
-    impl RangeDelta {
-        fn parse(l: &str) -> RangeDelta {
-            let mut iter = l.trim().split_whitespace();
-            let dest_start = iter.next().unwrap().parse::<isize>().unwrap();
-            let source_start = iter.next().unwrap().parse::<isize>().unwrap();
-            let length = iter.next().unwrap().parse::<isize>().unwrap();
-
-            RangeDelta {
-                in_range: source_start..source_start + length,
-                delta: dest_start as isize - source_start as isize,
-            }
+        Piece {
+            source: source_start..source_start + length,
+            destination,
         }
     }
 
     #[derive(PartialEq)]
     #[derive(Debug)]
     struct Layer {
-        ranges: Vec<RangeDelta>,
+        map: RangeMap<usize>,
     }
 
     #[derive(PartialEq)]
@@ -39,39 +29,38 @@ mod year2023day5 {
 
     impl Input {
         fn parse(file_name: &str) -> Input {
-            let mut lines = read_lines(file_name).into_iter();
+            let mut lines = read_lines(file_name);
 
             // parse seed line
             let mut seeds = Vec::new();
             if let Some(l) = lines.next() {
-                for d in l.unwrap().trim().split_whitespace() {
+                for d in l.unwrap().split_whitespace() {
                     if let Ok(d) = d.parse::<usize>() {
                         seeds.push(d);
                     }
                 }
             }
             let mut layers = Vec::new();
-            let mut name: Option<String> = None;
-            let mut ranges = Vec::new();
-            while let Some(l) = lines.next() {
+            let mut pieces = Vec::new();
+            let mut in_layer = false;
+            for l in lines {
                 match l.unwrap() {
                     e if e.is_empty() => {
-                        if name.is_some() {
-                            layers.push(Layer { ranges: ranges.clone() });
-                            name = None;
-                            ranges = Vec::new();
+                        if in_layer {
+                            layers.push(Layer { map: RangeMap::from_pieces(pieces.drain(..)) });
+                            in_layer = false;
                         }
                     }
                     n if n.ends_with(':') => {
-                        name = Some(n[..n.len() - " map:".len()].to_string());
+                        in_layer = true;
                     }
                     r => {
-                        ranges.push(RangeDelta::parse(&r));
+                        pieces.push(parse_piece(&r));
                     }
                 }
             }
-            if name.is_some() {
-                layers.push(Layer { ranges: ranges.clone() });
+            if in_layer {
+                layers.push(Layer { map: RangeMap::from_pieces(pieces) });
             }
 
             Input {
@@ -80,89 +69,35 @@ mod year2023day5 {
             }
         }
 
-        fn part1(&self) -> isize {
-            let mut lowest = isize::MAX;
-            for seed in &self.seeds {
-                let mut location = seed.clone() as isize;
-                for layer in &self.layers {
-                    let delta = layer.ranges.iter().find(|rd| {
-                        rd.in_range.contains(&location)
-                    }).map(|rd| { rd.delta }).unwrap_or(0);
-                    location += delta;
-                }
-                if location < lowest {
-                    lowest = location
-                }
-            }
-            lowest
+        fn almanac(&self) -> RangeMap<usize> {
+            self.layers.iter().fold(RangeMap::new(), |map, layer| map.compose(&layer.map))
         }
 
-        fn part2(&self) -> isize {
-            let mut seed_ranges: Vec<Range<isize>> = Vec::new();
-            let mut seed_iter = self.seeds.iter();
-            while let (Some(a), Some(b)) = (seed_iter.next(), seed_iter.next()) {
-                seed_ranges.push((*a as isize)..((a+b) as isize));
-            }
-            seed_ranges.sort_by_key(|r| r.start);
-            
-            let mut i = seed_ranges.first().unwrap().start as isize;
-            let mut best = isize::MAX;
-            while i < isize::MAX {
-                let mut out = i;
-                let mut delta = isize::MAX - i;
-                for l in &self.layers {
-                    if let Some(rd) = 
-                        l.ranges.iter().find(|rd| {rd.in_range.contains(&out)}) 
-                    {
-                        if rd.in_range.end > out && rd.in_range.end - out < delta {
-                            delta = rd.in_range.end - out;
-                        }
-                        out = out + rd.delta;
-                    } else {
-                        let mut potential_delta = isize::MAX - out;
-                        for rd in &l.ranges {
-                            if rd.in_range.start > out && rd.in_range.start - out < potential_delta {
-                                potential_delta = rd.in_range.start - out;
-                            }
-                            if potential_delta < delta {
-                                delta = potential_delta;
-                            }
-                        }
-                    }
-                }
-                if out < best {
-                    best = out;
-                }
-                if let Some(_) = 
-                    seed_ranges.iter().find(|r| {
-                        r.contains( &(i + delta))
-                    })
-                {
-                    i += delta
-                } else if let Some(r) = seed_ranges.iter().find(|r| {
-                    r.start > i + delta
-                }) {
-                    i = r.start;
-                } else {
-                    i = isize::MAX;
-                }
-            }
-            
-            best
+        fn part1(&self) -> usize {
+            let almanac = self.almanac();
+            self.seeds.iter().map(|&seed| almanac.get(seed)).min().unwrap()
+        }
+
+        fn part2(&self) -> usize {
+            let seeds = IntervalSet::from_ranges(
+                self.seeds.chunks(2).map(|pair| pair[0]..pair[0] + pair[1])
+            );
+            self.almanac().map_set(&seeds).min().unwrap()
         }
     }
 
     #[cfg(test)]
     mod tests {
         mod parse {
-            use crate::year2023day5::year2023day5::{Input, Layer, RangeDelta};
+            use crate::interval::{Piece, RangeMap};
+            use crate::year2023day5::year2023day5::{parse_piece, Input, Layer};
 
             #[test]
-            fn handle_parse_range_delta() {
-                let parsed = RangeDelta::parse("50 98 2");
-                let expected = RangeDelta {
-                    in_range: 98..100,
-                    delta: -48,
+            fn handle_parse_piece() {
+                let parsed = parse_piece("50 98 2");
+                let expected = Piece {
+                    source: 98..100,
+                    destination: 50,
                 };
                 assert_eq!(parsed, expected);
             }
@@ -177,13 +112,13 @@ mod year2023day5 {
                 assert_eq!(expected.seeds, parsed.seeds);
 
                 let expected_first_layer = Layer {
-                    ranges: vec![RangeDelta {
-                        in_range: 98..100,
-                        delta: -48,
-                    }, RangeDelta {
-                        in_range: 50..98,
-                        delta: 2,
-                    }]
+                    map: RangeMap::from_pieces([Piece {
+                        source: 98..100,
+                        destination: 50,
+                    }, Piece {
+                        source: 50..98,
+                        destination: 52,
+                    }])
                 };
                 assert_eq!(&expected_first_layer, parsed.layers.first().unwrap());
                 assert_eq!(7, parsed.layers.len());
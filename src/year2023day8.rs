@@ -1,210 +1,157 @@
 mod year2023day8 {
-    use crate::read_lines;
-    use regex::Regex;
-    use std::collections::HashMap;
-
-    struct MapNode {
-        left: String,
-        right: String
-    }
-
-    #[derive(PartialEq, Debug, Eq)]
-    enum Direction {
-        Left, Right
-    }
-
+    use crate::coord::Turn;
+    use crate::cycle::find_cycle;
+    use crate::graph::Graph;
+    use crate::interner::{Id, Interner};
+    use crate::number_theory::crt;
+    use crate::parse::{scan, sections};
+    use crate::read_string;
+
+    // Every node has exactly two successors, added left then right
     struct Input {
-        directions: Vec<Direction>,
-        nodes: HashMap<String, MapNode>
+        directions: Vec<Turn>,
+        network: Graph<String>
     }
 
     impl Input {
-        fn parse_directions(line: &str) -> Vec<Direction> {
+        fn parse_directions(line: &str) -> Vec<Turn> {
             let mut result = Vec::new();
             for ch in line.chars() {
-                match ch {
-                    'R' => { result.push(Direction::Right) }
-                    'L' => { result.push(Direction::Left) }
-                    c => panic!("Invalid character in input: {}", c)
+                match Turn::parse(ch) {
+                    Some(d) => result.push(d),
+                    None => panic!("Invalid character in input: {}", ch)
                 }
             }
             result
         }
 
         fn new(filename: &str) -> Self {
-            let mut lines_iter = read_lines(filename);
-            let mut nodes = HashMap::new();
-
-            let directions = Self::parse_directions(lines_iter.next().unwrap().unwrap().as_str());
-            let pattern = Regex::new(r"(\w+) = \((\w+), (\w+)\)").unwrap();
-            while let Some(Ok(line)) = lines_iter.next() {
-                if line.is_empty() { continue }
-                let parts = pattern.captures(&line).unwrap();
-                let root = parts[1].to_string();
-                let left = parts[2].to_string();
-                let right = parts[3].to_string();
-                nodes.insert(root.clone(), MapNode { left, right });
-            }
+            let input = read_string(filename);
+            let sections = sections(&input);
+
+            let directions = Self::parse_directions(sections[0].lines[0]);
+            let mut names = Interner::new();
+            let nodes = sections[1].parse_lines(|line| {
+                scan!("{} = ({}, {})", line => String, String, String)
+            }).unwrap();
+            let edges: Vec<(Id, Id)> = nodes.iter()
+                .flat_map(|(root, left, right)| {
+                    let root = names.intern_str(root);
+                    [(root, names.intern_str(left)), (root, names.intern_str(right))]
+                })
+                .collect();
+            let mut network = Graph::directed_with(names);
+            network.extend_ids(edges);
 
             Input {
-                directions, nodes
+                directions, network
+            }
+        }
+
+        fn next(&self, node: Id, turn: Turn) -> Id {
+            let successors = self.network.successor_ids(node);
+            match turn {
+                Turn::Left => successors[0],
+                Turn::Right => successors[1],
             }
         }
     }
     
     fn follow_path(input: Input) -> usize {
-        let mut pointer = "AAA";
+        let mut pointer = input.network.node_id("AAA").unwrap();
+        let end = input.network.node_id("ZZZ").unwrap();
         let mut counter = 0;
-        while pointer != "ZZZ" {
-            match input.directions[counter % input.directions.len()] {
-                Direction::Left => {
-                    pointer = input.nodes.get(pointer).unwrap().left.as_str();
-                }
-                Direction::Right => {
-                    pointer = input.nodes.get(pointer).unwrap().right.as_str();
-                }
-            }
+        while pointer != end {
+            pointer = input.next(pointer, input.directions[counter % input.directions.len()]);
             counter += 1;
         }
         counter
     }
 
     fn follow_ghost_path(input: Input) -> usize {
-        #[derive(Copy, Clone, Debug)]
-        struct Node {
-            is_end: bool,
-            left: usize,
-            right: usize
-        }
-
-        #[derive(Copy, Clone, Debug)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
         struct State {
-            pos: usize,
+            pos: Id,
             dir_idx: usize
         }
 
         impl State {
-            fn next(&self, nodes: &[Node], directions: &[Direction]) -> State {
-                let next_dir_idx = (self.dir_idx + 1) % directions.len();
-                let next_pos = match directions[self.dir_idx] {
-                    Direction::Left => nodes[self.pos].left,
-                    Direction::Right => nodes[self.pos].right,
-                };
+            fn next(&self, input: &Input) -> State {
+                let next_dir_idx = (self.dir_idx + 1) % input.directions.len();
+                let next_pos = input.next(self.pos, input.directions[self.dir_idx]);
                 State { pos: next_pos, dir_idx: next_dir_idx }
             }
         }
 
-        // Build nodes array similar to before
-        let mut nodes = Vec::with_capacity(input.nodes.len());
-        let mut start_positions = Vec::new();
-        let mut node_map = HashMap::new();
-
-        for (idx, key) in input.nodes.keys().enumerate() {
-            node_map.insert(key.as_str(), idx);
-            if key.ends_with('A') {
-                start_positions.push(idx);
-            }
-            nodes.push(Node {
-                is_end: key.ends_with('Z'),
-                left: 0,
-                right: 0
-            });
-        }
-
-        for (key, node) in input.nodes.iter() {
-            let idx = node_map[key.as_str()];
-            nodes[idx].left = node_map[node.left.as_str()];
-            nodes[idx].right = node_map[node.right.as_str()];
-        }
+        let is_end = |id: Id| input.network.node(id).ends_with('Z');
+        let starts: Vec<State> = (0..input.network.len() as Id)
+            .filter(|&id| input.network.node(id).ends_with('A'))
+            .map(|pos| State { pos, dir_idx: 0 })
+            .collect();
+        let step = |s: &State| s.next(&input);
 
         // Find cycle information for each starting position
         let mut cycle_info = Vec::new();
 
-        for &start_pos in &start_positions {
-            // Initialize tortoise and hare
-            let mut tortoise = State { pos: start_pos, dir_idx: 0 };
-            let mut hare = State { pos: start_pos, dir_idx: 0 };
+        for &start in &starts {
+            let cycle = find_cycle(start, step);
+            let z_positions = cycle.matches(step, |s| is_end(s.pos));
 
-            // Phase 1: Find a point in the cycle
-            loop {
-                tortoise = tortoise.next(&nodes, &input.directions);
-                hare = hare.next(&nodes, &input.directions);
-                hare = hare.next(&nodes, &input.directions);
-
-                if tortoise.pos == hare.pos && tortoise.dir_idx == hare.dir_idx {
-                    break;
-                }
-            }
-
-            // Phase 2: Find cycle start
-            let mut cycle_start = State { pos: start_pos, dir_idx: 0 };
-            let mut steps_to_cycle = 0;
-            while cycle_start.pos != tortoise.pos || cycle_start.dir_idx != tortoise.dir_idx {
-                cycle_start = cycle_start.next(&nodes, &input.directions);
-                tortoise = tortoise.next(&nodes, &input.directions);
-                steps_to_cycle += 1;
-            }
-
-            // Phase 3: Find cycle length and Z positions
-            let mut cycle_length = 1;
-            let mut z_positions = Vec::new();
-            let mut current = cycle_start.next(&nodes, &input.directions);
-
-            // Record position if we're at a Z node
-            if nodes[cycle_start.pos].is_end {
-                z_positions.push(0);
-            }
-
-            while current.pos != cycle_start.pos || current.dir_idx != cycle_start.dir_idx {
-                if nodes[current.pos].is_end {
-                    z_positions.push(cycle_length);
-                }
-                current = current.next(&nodes, &input.directions);
-                cycle_length += 1;
-            }
-
-            cycle_info.push((steps_to_cycle, cycle_length, z_positions));
+            cycle_info.push((cycle.prefix, cycle.length, z_positions));
         }
 
-        // Now we need to find the least common multiple (LCM) of the cycle lengths
-        // adjusted for the phase shifts to Z positions
-        fn gcd(mut a: usize, mut b: usize) -> usize {
-            while b != 0 {
-                let temp = b;
-                b = a % b;
-                a = temp;
+        // Each ghost is on a Z at step t when t ≡ z (mod cycle length) for one of its cycle's
+        // Z positions, so try every combination and keep the earliest time they all line up
+        let earliest = cycle_info.iter().map(|(prefix, _, _)| *prefix).max().unwrap_or(0);
+
+        // Before every ghost is inside its cycle the congruences say nothing, so walk them all
+        let mut ghosts = starts;
+        for t in 1..earliest {
+            ghosts.iter_mut().for_each(|ghost| *ghost = step(ghost));
+            if ghosts.iter().all(|ghost| is_end(ghost.pos)) {
+                return t;
             }
-            a
         }
 
-        fn lcm(a: usize, b: usize) -> usize {
-            a * (b / gcd(a, b))
+        let mut combinations: Vec<Vec<(isize, isize)>> = vec![Vec::new()];
+        for (_, cycle_len, z_positions) in &cycle_info {
+            combinations = combinations.iter()
+                .flat_map(|combination| z_positions.iter().map(move |&z| {
+                    let mut extended = combination.clone();
+                    extended.push((z as isize, *cycle_len as isize));
+                    extended
+                }))
+                .collect();
         }
 
-        // For this specific puzzle, it turns out that:
-        // 1. Each path has exactly one Z position in its cycle
-        // 2. The Z position occurs at the end of the cycle
-        // 3. The steps to reach the cycle is equal to the first Z position
-        // These properties make the solution much simpler than the general case
-
-        cycle_info.iter()
-            .map(|(_, cycle_len, _)| *cycle_len)
-            .fold(1, lcm)
+        combinations.iter()
+            .filter_map(|congruences| crt(congruences))
+            .map(|(x, modulus)| {
+                let (x, modulus) = (x as usize, modulus as usize);
+                if x >= earliest.max(1) {
+                    x
+                } else {
+                    x + (earliest.max(1) - x).div_ceil(modulus) * modulus
+                }
+            })
+            .min()
+            .expect("The ghosts never all reach Z together")
     }
     
     #[cfg(test)]
     mod tests {
         mod input {
-            use crate::year2023day8::year2023day8::Direction::{Left, Right};
+            use crate::coord::Turn::{Left, Right};
             use crate::year2023day8::year2023day8::Input;
 
             #[test]
             fn parse_example() {
                 let input = Input::new("input/2023-08-e1.txt");
                 assert_eq!(input.directions, vec![Right, Left]);
-                assert_eq!(input.nodes.len(), 7);
-                assert!(input.nodes.contains_key("AAA"));
-                assert!(input.nodes.contains_key("ZZZ"));
+                assert_eq!(input.network.len(), 7);
+                assert!(input.network.contains("AAA"));
+                assert!(input.network.contains("ZZZ"));
+                assert_eq!(vec!["BBB", "CCC"], input.network.successors("AAA").collect::<Vec<_>>());
             }
         }
         
@@ -238,6 +185,13 @@ mod year2023day8 {
                 let input = Input::new("input/2023-08-e3.txt");
                 assert_eq!(follow_ghost_path(input), 6);
             }
+
+            #[test]
+            fn meet_before_cycling() {
+                // both ghosts pass their Z once on the way into a cycle without one
+                let input = Input::new("input/2023-08-t1.txt");
+                assert_eq!(follow_ghost_path(input), 1);
+            }
             
             #[test]
             fn solution() {
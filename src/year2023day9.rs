@@ -1,48 +1,28 @@
 mod year2023day9 {
+    use crate::polynomial::DifferenceTable;
     use crate::read_lines;
 
     struct Sequence {
-        levels: Vec<Vec<isize>>
+        table: DifferenceTable
     }
     
     impl Sequence {
         fn new(line: &str) -> Self {
-            let mut levels: Vec<Vec<isize>> = Vec::new();
+            let values: Vec<isize> = line.split_whitespace().map(|s| s.parse().unwrap()).collect();
+            Sequence { table: DifferenceTable::new(&values) }
+        }
 
-            levels.push(line.split_whitespace().map(|s| s.parse().unwrap()).collect());
-            
-            loop {
-                let last_level = levels.last().unwrap();
-                if last_level.iter().all ( |&x| x == 0 ) {
-                    break;
-                }
-                
-                let mut next_level = Vec::new();
-                for w in last_level.windows(2) {
-                    next_level.push(w[1] - w[0]);
-                }
-                levels.push(next_level);
-            }
-            
-            Sequence { levels }
+        // The value at position n, counting the first reading as 0
+        fn at(&self, n: isize) -> isize {
+            self.table.at(n)
         }
         
         fn predict(&self) -> isize {
-            let mut prediction = 0;
-            for l in self.levels.iter().rev() {
-                prediction = prediction + l.last().unwrap();
-            }
-            
-            prediction
+            self.at(self.table.levels()[0].len() as isize)
         }
         
         fn predict_left(&self) -> isize {
-            let mut prediction = 0;
-            for l in self.levels.iter().rev() {
-                prediction = l.first().unwrap() - prediction;
-            }
-
-            prediction
+            self.at(-1)
         }
     }
     
@@ -73,9 +53,17 @@ mod year2023day9 {
 
             #[test]
             fn parse_example1() {
-                let actual = Sequence::new("0 3 6 9 12 15").levels.len();
+                let actual = Sequence::new("0 3 6 9 12 15").table.levels().len();
                 assert_eq!(actual, 3);
             }
+
+            #[test]
+            fn at() {
+                let sequence = Sequence::new("1 3 6 10 15 21");
+                assert_eq!(6, sequence.at(2));
+                assert_eq!(5050, sequence.at(99));
+                assert_eq!(0, sequence.at(-1));
+            }
             
             #[test]
             fn predict_example1() {
@@ -1,107 +1,47 @@
 mod year2024day10 {
-    use crate::read_lines;
-    use std::collections::HashSet;
+    use crate::grid::{Cell, Grid};
+    use crate::search;
+    use crate::search::Search;
     use std::sync::mpsc;
     use std::thread;
 
-    type TrailMap = Vec<Vec<usize>>;
-    type Coord = (usize, usize);
+    type TrailMap = Grid<usize>;
+    type Coord = Cell;
 
     fn parse_trail_map(filename: &str) -> TrailMap {
-        let mut m = Vec::new();
-
-        let mut lines = read_lines(filename);
-        while let Some(Ok(line)) = lines.next() {
-            m.push(line.chars()
-                .map(|c| {
-                    match c.to_digit(10) {
-                        Some(d) => d as usize,
-                        _ => usize::MAX
-                    }
-                })
-                .collect());
-        }
+        Grid::read(filename, |c| {
+            match c.to_digit(10) {
+                Some(d) => d as usize,
+                _ => usize::MAX
+            }
+        })
+    }
 
-        m
+    fn climb(trailhead: Coord, trail_map: &TrailMap) -> Search<Coord> {
+        search::bfs(
+            [trailhead],
+            |&current| {
+                let next_elevation = trail_map[current] + 1;
+                trail_map.neighbours4(current)
+                    .filter(move |&next| trail_map[next] == next_elevation)
+            },
+            |_| false,
+        )
     }
 
     fn wander_part_1(trailhead: Coord, trail_map: TrailMap) -> usize {
-        let mut peaks: HashSet<Coord> = HashSet::new();
-        let height = trail_map.len();
-        let width = trail_map[0].len();
-        let mut options = vec![trailhead];
-        while !options.is_empty() {
-            let current = options.pop().unwrap();
-            let next_elevation = trail_map[current.0][current.1] + 1;
-            if next_elevation == 10 {
-                peaks.insert(current);
-                continue;
-            }
-            // Up
-            if current.0 > 0 && trail_map[current.0 - 1][current.1] == next_elevation {
-                options.push((current.0 - 1, current.1));
-            }
-            // Down
-            if current.0 < height - 1 && trail_map[current.0 + 1][current.1] == next_elevation {
-                options.push((current.0 + 1, current.1));
-            }
-            // Left
-            if current.1 > 0 && trail_map[current.0][current.1 - 1] == next_elevation {
-                options.push((current.0, current.1 - 1));
-            }
-            // Right
-            if current.1 < width - 1 && trail_map[current.0][current.1 + 1] == next_elevation {
-                options.push((current.0, current.1 + 1));
-            }
-        }
-        peaks.len()
+        climb(trailhead, &trail_map)
+            .visited()
+            .filter(|&&c| trail_map[c] == 9)
+            .count()
     }
-    
-    type Trail = Vec<Coord>;
 
     fn wander_part_2(trailhead: Coord, trail_map: TrailMap) -> usize {
-        let mut trails: HashSet<Trail> = HashSet::new();
-        let height = trail_map.len();
-        let width = trail_map[0].len();
-        let mut options: Vec<Trail> = vec![vec![trailhead]];
-        while !options.is_empty() {
-            let current_trail = options.pop().unwrap();
-            let current = current_trail.last().unwrap();
-            let next_elevation = trail_map[current.0][current.1] + 1;
-            if next_elevation == 10 {
-                trails.insert(current_trail);
-                continue;
-            }
-            // Up
-            if current.0 > 0 && trail_map[current.0 - 1][current.1] == next_elevation {
-                let up = (current.0 - 1, current.1);
-                let mut trail = current_trail.clone();
-                trail.push(up);
-                options.push(trail);
-            }
-            // Down
-            if current.0 < height - 1 && trail_map[current.0 + 1][current.1] == next_elevation {
-                let down = (current.0 + 1, current.1);
-                let mut trail = current_trail.clone();
-                trail.push(down);
-                options.push(trail);
-            }
-            // Left
-            if current.1 > 0 && trail_map[current.0][current.1 - 1] == next_elevation {
-                let left = (current.0, current.1 - 1);
-                let mut trail = current_trail.clone();
-                trail.push(left);
-                options.push(trail);
-            }
-            // Right
-            if current.1 < width - 1 && trail_map[current.0][current.1 + 1] == next_elevation {
-                let right = (current.0, current.1 + 1);
-                let mut trail = current_trail.clone();
-                trail.push(right);
-                options.push(trail);
-            }
-        }
-        trails.len()
+        let trails = climb(trailhead, &trail_map);
+        trails.visited()
+            .filter(|&&c| trail_map[c] == 9)
+            .map(|peak| trails.count_paths_to(peak))
+            .sum()
     }
 
     fn evaluate_trail_map(
@@ -109,14 +49,7 @@ mod year2024day10 {
         wander: fn(Coord, TrailMap) -> usize,
     ) -> usize {
         let trail_map = parse_trail_map(filename);
-        let mut trailheads: Vec<Coord> = Vec::new();
-        for (r, row) in trail_map.iter().enumerate() {
-            for (c, &elevation) in row.iter().enumerate() {
-                if elevation == 0 {
-                    trailheads.push((r, c));
-                }
-            }
-        }
+        let trailheads: Vec<Coord> = trail_map.find_all(|&elevation| elevation == 0).collect();
         let mut score = 0;
 
         let (tx, rx) = mpsc::channel();
@@ -1,3 +1,4 @@
+use crate::digits;
 use std::collections::HashMap;
 
 #[derive(Clone)]
@@ -38,10 +39,7 @@ fn do_blinks(filename: &str, n: usize) -> usize {
         for stone in stones.values() {
             if stone.value == 0 {
                 temp.push(stone.with_value(1));
-            } else if stone.value.to_string().len() % 2 == 0 {
-                let s = stone.value.to_string();
-                let a = s[..s.len() / 2].parse::<usize>().unwrap();
-                let b = s[s.len() / 2..].parse::<usize>().unwrap();
+            } else if let Some((a, b)) = digits::halves(stone.value) {
                 temp.push(stone.with_value(a));
                 temp.push(stone.with_value(b));
             } else {
@@ -1,5 +1,9 @@
-use std::cmp::Ordering;
-use crate::read_lines;
+use crate::bitgrid::BitGrid;
+use crate::coord::Direction::{self, *};
+use crate::coord::Point;
+use crate::flood;
+use crate::grid::Grid;
+use crate::svg::Svg;
 
 struct Garden {
     regions: Vec<Region>,
@@ -8,121 +12,76 @@ struct Garden {
 #[derive(PartialEq, Eq)]
 struct Region {
     id: char,
-    coords: Vec<Coord>
+    coords: Vec<Coord>,
+    plots: BitGrid,
 }
 
 impl Region {
-    fn is_adjacent_coord(&self, other: &Coord) -> bool {
-        self.coords.contains(&other) || self.coords.iter().any(|me| {
-            coords_are_adjacent(me, other)
-        })
-    }
-
     fn area(&self) -> usize {
         self.coords.len()
     }
 
+    // Plots whose neighbour in this direction is outside the region
+    fn fences(&self, direction: Direction) -> BitGrid {
+        &self.plots - &self.plots.shifted(direction.reverse())
+    }
+
     fn perimeter(&self) -> usize {
-        self.coords.iter().map(|coord| {
-            4 - self.coords.iter().filter(|other| {
-                *other != coord && coords_are_adjacent(coord, other)
-            }).count()
-        }).sum()
+        Direction::ALL.into_iter().map(|direction| self.fences(direction).count_ones()).sum()
     }
     
     fn side_count(&self) -> usize {
-        let row_first_sort = |a: &&Coord, b: &&Coord| -> Ordering {
-            let row = a.0.cmp(&b.0);
-            if row != Ordering::Equal {
-                row
-            } else {
-                a.1.cmp(&b.1)
-            }
-        };
-        let col_first_sort = |a: &&Coord, b: &&Coord| -> Ordering {
-            let col = a.1.cmp(&b.1);
-            if col != Ordering::Equal {
-                col
-            } else {
-                a.0.cmp(&b.0)
-            }
-        };
-        let mut segments = 4;
-        let mut ups = self.coords.iter().filter(|coord| {
-            coord.0 == 0 || !self.coords.contains(&(coord.0 - 1, coord.1))
-        }).collect::<Vec<_>>();
-        ups.sort_by(row_first_sort);
-        for window in ups.windows(2) {
-            if window[0].0 != window[1].0 || window[0].1 + 1 != window[1].1 {
-                segments += 1;
-            }
-        }
-        
-        let mut rights = self.coords.iter().filter(|coord| {
-            !self.coords.contains(&(coord.0, coord.1 + 1))
-        }).collect::<Vec<_>>(); 
-        rights.sort_by(col_first_sort);
-        for window in rights.windows(2) {
-            if window[0].1 != window[1].1 || window[0].0 + 1 != window[1].0 {
-                segments += 1;
-            }
-        }
-        
-        let mut downs = self.coords.iter().filter(|coord| {
-            !self.coords.contains(&(coord.0 + 1, coord.1))
-        }).collect::<Vec<_>>();
-        downs.sort_by(row_first_sort);
-        for window in downs.windows(2) {
-            if window[0].0 != window[1].0 || window[0].1 + 1 != window[1].1 {
-                segments += 1;
-            }
-        }
-        
-        let mut lefts = self.coords.iter().filter(|coord| {
-            coord.1 == 0 || !self.coords.contains(&(coord.0, coord.1 - 1))
-        }).collect::<Vec<_>>();
-        lefts.sort_by(col_first_sort);
-        for window in lefts.windows(2) {
-            if window[0].1 != window[1].1 || window[0].0 + 1 != window[1].0 {
-                segments += 1;
+        self.sides().len()
+    }
+
+    // Each side as its two end corners, with corner (x, y) at the top left of cell (y, x)
+    fn sides(&self) -> Vec<(Point, Point)> {
+        let corner = |r: usize, c: usize| Point::new(c as isize, r as isize);
+        let mut sides = Vec::new();
+        for direction in Direction::ALL {
+            // fences facing this way, keyed by the line they lie on and then their position along it
+            let mut fences = self.fences(direction).iter()
+                .map(|(r, c)| match direction {
+                    North => (r, c),
+                    South => (r + 1, c),
+                    West => (c, r),
+                    East => (c + 1, r),
+                })
+                .collect::<Vec<_>>();
+            fences.sort();
+
+            let mut run_start = 0;
+            for i in 0..fences.len() {
+                let (line, along) = fences[i];
+                let continues = fences.get(i + 1).is_some_and(|&next| next == (line, along + 1));
+                if continues {
+                    continue;
+                }
+                let first = fences[run_start].1;
+                sides.push(if direction.is_vertical() {
+                    (corner(line, first), corner(line, along + 1))
+                } else {
+                    (corner(first, line), corner(along + 1, line))
+                });
+                run_start = i + 1;
             }
         }
-        segments
+        sides
     }
 }
 
 type Coord = (usize, usize);
 
-fn coords_are_adjacent(a: &Coord, b: &Coord) -> bool {
-    (a.0.abs_diff(b.0) == 1 && a.1.abs_diff(b.1) == 0) ||
-        (a.0.abs_diff(b.0) == 0 && a.1.abs_diff(b.1) == 1)
-}
-
 impl Garden {
     fn new(filename: &str) -> Garden {
-        let mut regions: Vec<Region> = Vec::new();
-        let mut lines = read_lines(filename);
-        let mut row = 0;
-        while let Some(Ok(line)) = lines.next() {
-            for (col, c) in line.chars().enumerate() {
-                let mut adjacents = regions.iter_mut().filter(|r| {
-                    r.id == c && r.is_adjacent_coord(&(row, col))
-                }).collect::<Vec<&mut Region>>();
-                if adjacents.is_empty() {
-                    regions.push(Region { id: c, coords: vec![(row, col)] });
-                } else if adjacents.len() == 1 {
-                    adjacents[0].coords.push((row, col));
-                } else {
-                    let mut coords = vec![(row, col)];
-                    for r in adjacents {
-                        coords.append(r.coords.as_mut());
-                    }
-                    regions.push(Region { id: c, coords });
-                }
-            }
-            row += 1;
-        }
-        regions.retain(|r| !r.coords.is_empty());
+        let plots = Grid::read(filename, |c| c);
+        let regions = flood::label(&plots, |a, b| a == b).components.into_iter()
+            .map(|component| Region {
+                id: plots[component.cells[0]],
+                plots: BitGrid::from_cells(plots.height(), plots.width(), component.cells.iter().copied()),
+                coords: component.cells,
+            })
+            .collect();
 
         Garden {
             regions
@@ -138,6 +97,23 @@ impl Garden {
     }
 }
 
+// Every region in its own colour, outlined by its fence sides
+pub fn svg(filename: &str) -> Svg {
+    let plots = Grid::read(filename, |c| c);
+    let garden = Garden::new(filename);
+    let mut svg = Svg::new(plots.width(), plots.height(), 16);
+    for region in &garden.regions {
+        let hue = (region.id as usize * 47) % 360;
+        svg.cells(region.coords.iter().map(|&c| Point::from_cell(c)), &format!("hsl({}, 60%, 75%)", hue));
+    }
+    for region in &garden.regions {
+        for (from, to) in region.sides() {
+            svg.edge(from, to, "black", 2.0);
+        }
+    }
+    svg
+}
+
 #[cfg(test)]
 mod tests {
     mod part1 {
@@ -200,4 +176,18 @@ mod tests {
             assert_eq!(805814, garden.price_by_side());
         }
     }
+
+    mod svg {
+        use crate::year2024day12::svg;
+
+        #[test]
+        fn example() {
+            let text = svg("input/2024-12-e1.txt").to_string();
+            assert_eq!(16, text.matches("<rect x=").count());
+            // the sides counted by part 2: 4 + 4 + 8 + 4 + 4
+            assert_eq!(24, text.matches("<line").count());
+            // the lone D plot in the top right corner is fenced on all four sides
+            assert!(text.contains(r#"<line x1="48" y1="16" x2="64" y2="16""#));
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
-use regex::Regex;
+use crate::checked::{ArithmeticError, Checked};
+use crate::parse::{integers, sections, ParseError};
+use crate::rational::{solve, Rational, Solution};
 use crate::read_string;
 
 struct MachineDetails {
@@ -12,36 +14,72 @@ struct MachineDetails {
 
 impl MachineDetails {
     fn read_file(filename: &str, offset: isize) -> Vec<Self> {
-        let mut machines = Vec::new();
-        let pattern = Regex::new(
-            r"Button A: X\+(\d+), Y\+(\d+)\nButton B: X\+(\d+), Y\+(\d+)\nPrize: X=(\d+), Y=(\d+)\n?"
-        ).unwrap();
         let input = read_string(filename);
-        for capture in pattern.captures_iter(input.as_str()) {
-            machines.push(Self {
-                a_x_delta: capture[1].parse().unwrap(),
-                a_y_delta: capture[2].parse().unwrap(),
-                b_x_delta: capture[3].parse().unwrap(),
-                b_y_delta: capture[4].parse().unwrap(),
-                prize_x: capture[5].parse::<isize>().unwrap() + offset,
-                prize_y: capture[6].parse::<isize>().unwrap() + offset,
+        sections(&input).iter().map(|section| {
+            let numbers: Vec<isize> = section.parse_lines(integers)?.concat();
+            let [a_x_delta, a_y_delta, b_x_delta, b_y_delta, prize_x, prize_y] = numbers[..] else {
+                return Err(ParseError::new(1, format!("expected 6 numbers, found {}", numbers.len()))
+                    .at_line(section.first_line));
+            };
+            Ok(Self {
+                a_x_delta,
+                a_y_delta,
+                b_x_delta,
+                b_y_delta,
+                prize_x: prize_x + offset,
+                prize_y: prize_y + offset,
             })
-        }
-        
-        machines
+        }).collect::<Result<_, _>>().unwrap()
     }
     
-    fn cost(&self) -> Option<usize> {
-        let b = (self.prize_x*self.a_y_delta - self.prize_y*self.a_x_delta) /
-            (self.b_x_delta*self.a_y_delta - self.b_y_delta*self.a_x_delta);
-        let a = (self.prize_x-b*self.b_x_delta) / self.a_x_delta;
-        
-        if self.a_x_delta*a + self.b_x_delta*b == self.prize_x &&
-            self.a_y_delta*a + self.b_y_delta*b == self.prize_y 
-        {
-            Some((3 * a + b) as usize)
+    // None when the prize cannot be reached, an error if the presses cannot be costed in a usize
+    fn cost(&self) -> Result<Option<usize>, ArithmeticError> {
+        let coefficients = vec![
+            vec![Rational::from(self.a_x_delta), Rational::from(self.b_x_delta)],
+            vec![Rational::from(self.a_y_delta), Rational::from(self.b_y_delta)],
+        ];
+        let prize = [Rational::from(self.prize_x), Rational::from(self.prize_y)];
+        let solution = solve(&coefficients, &prize).ok_or(ArithmeticError::Overflow { operation: "solve" })?;
+        match solution {
+            Solution::Unique(_) => {
+                let Some(presses) = solution.integer_solution() else { return Ok(None) };
+                let (a, b) = (presses[0], presses[1]);
+                if a < 0 || b < 0 {
+                    return Ok(None);
+                }
+                (Checked::new(a) * 3 + b).cast().get().map(Some)
+            }
+            Solution::Infinite { .. } => self.cheapest_on_line(),
+            Solution::None => Ok(None),
+        }
+    }
+
+    // Both buttons move along the same line as the prize, so any mix that reaches it along one
+    // axis reaches it along the other. Press the button that is cheaper per unit of distance as
+    // much as possible; the remainder repeats after that many presses of the other button.
+    fn cheapest_on_line(&self) -> Result<Option<usize>, ArithmeticError> {
+        let (a_delta, b_delta, prize) = if self.a_x_delta != 0 || self.b_x_delta != 0 {
+            (self.a_x_delta, self.b_x_delta, self.prize_x)
+        } else {
+            (self.a_y_delta, self.b_y_delta, self.prize_y)
+        };
+        let presses_for = |fewer_delta: isize, more_delta: isize| -> Result<Option<(isize, isize)>, ArithmeticError> {
+            for fewer in 0..=more_delta.max(1) {
+                let remaining = (Checked::new(prize) - Checked::new(fewer) * fewer_delta).get()?;
+                if remaining < 0 {
+                    break;
+                }
+                if more_delta != 0 && remaining % more_delta == 0 {
+                    return Ok(Some((fewer, remaining / more_delta)));
+                }
+            }
+            Ok(None)
+        };
+        let cost = |a: isize, b: isize| (Checked::new(a) * 3 + b).cast().get();
+        if (Checked::new(b_delta) * 3).get()? >= a_delta {
+            presses_for(a_delta, b_delta)?.map(|(a, b)| cost(a, b)).transpose()
         } else {
-            None
+            presses_for(b_delta, a_delta)?.map(|(b, a)| cost(a, b)).transpose()
         }
     }
 }
@@ -60,14 +98,14 @@ mod tests {
         #[test]
         fn example() {
             let machine_details = MachineDetails::read_file("input/2024-13-e1.txt", 0);
-            let cost: usize = machine_details.iter().filter_map(|m| m.cost()).sum();
+            let cost: usize = machine_details.iter().filter_map(|m| m.cost().unwrap()).sum();
             assert_eq!(480, cost);
         }
 
         #[test]
         fn solution() {
             let machine_details = MachineDetails::read_file("input/2024-13-input.txt", 0);
-            let cost: usize = machine_details.iter().filter_map(|m| m.cost()).sum();
+            let cost: usize = machine_details.iter().filter_map(|m| m.cost().unwrap()).sum();
             assert_eq!(28753, cost);
         }
     }
@@ -78,8 +116,38 @@ mod tests {
         #[test]
         fn solution() {
             let machine_details = MachineDetails::read_file("input/2024-13-input.txt", 10000000000000);
-            let cost: usize = machine_details.iter().filter_map(|m| m.cost()).sum();
+            let cost: usize = machine_details.iter().filter_map(|m| m.cost().unwrap()).sum();
             assert_eq!(102718967795500, cost);
         }
     }
+
+    mod degenerate {
+        use crate::year2024day13::MachineDetails;
+
+        fn machine(a: (isize, isize), b: (isize, isize), prize: (isize, isize)) -> MachineDetails {
+            MachineDetails {
+                a_x_delta: a.0, a_y_delta: a.1,
+                b_x_delta: b.0, b_y_delta: b.1,
+                prize_x: prize.0, prize_y: prize.1,
+            }
+        }
+
+        #[test]
+        fn collinear_buttons() {
+            // B is cheaper per step, so use as many B presses as possible
+            assert_eq!(Ok(Some(11)), machine((4, 8), (2, 4), (22, 44)).cost());
+            // A covers more than three times the distance of B, so A is cheaper
+            assert_eq!(Ok(Some(10)), machine((10, 10), (1, 1), (31, 31)).cost());
+            assert_eq!(Ok(None), machine((4, 8), (2, 4), (23, 46)).cost());
+            assert_eq!(Ok(None), machine((4, 8), (2, 4), (22, 45)).cost());
+        }
+
+        #[test]
+        fn overflow() {
+            // isize::MAX presses of A cost more than a usize can hold
+            assert!(machine((1, 0), (0, 1), (isize::MAX, 1)).cost().is_err());
+            let huge = isize::MAX / 2;
+            assert!(machine((1, 1), (huge, huge), (isize::MAX, isize::MAX)).cost().is_err());
+        }
+    }
 }
\ No newline at end of file
@@ -1,162 +1,93 @@
-use crate::read_lines;
-use regex::Regex;
-use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io;
-use std::io::Write;
+use crate::coord::{Point, Vector};
+use crate::image::{Image, Rgb};
+use crate::number_theory::crt;
+use crate::parse::{parse_lines, scan};
+use crate::read_string;
+use crate::torus::TorusGrid;
 
 type XY = (isize, isize);
 
+// Robots walk straight through the walls and come back on the other side; each tile counts
+// the robots standing on it
+type Room = TorusGrid<usize>;
+
 struct InitialRobot {
-    position: XY,
-    velocity: XY
+    position: Point,
+    velocity: Vector
 }
 
-fn parse(filenamee: &str) -> Vec<InitialRobot> {
-    let mut robots = Vec::new();
-    let pattern = Regex::new(r"^p=([+\-\d]+),([+\-\d]+) v=([+\-\d]+),([+\-\d]+)$").unwrap();
-    let mut lines = read_lines(filenamee);
-    while let Some(Ok(line)) = lines.next() {
-        let captures = pattern.captures(line.as_str()).unwrap();
-        robots.push(InitialRobot {
-            position: (captures[1].parse().unwrap(), captures[2].parse().unwrap()),
-            velocity: (captures[3].parse().unwrap(), captures[4].parse().unwrap())
-        })
-    }
-    
-    robots
+fn parse(filename: &str) -> Vec<InitialRobot> {
+    parse_lines(&read_string(filename), |line| {
+        scan!("p={},{} v={},{}", line => isize, isize, isize, isize)
+            .map(|(px, py, vx, vy)| InitialRobot { position: Point::new(px, py), velocity: Vector::new(vx, vy) })
+    }).unwrap()
 }
 
-fn part1(filename: &str, room: XY) -> usize {
+fn empty_room(size: XY) -> Room {
+    TorusGrid::new(size.1 as usize, size.0 as usize, 0)
+}
+
+fn part1(filename: &str, size: XY) -> usize {
     let robots = parse(filename);
-    let mid_x = room.0 / 2;
-    let mid_y = room.1 / 2;
-    let quadrants = robots.iter().filter_map(|robot| {
-        let mut x = (robot.position.0 + robot.velocity.0 * 100) % room.0;
-        while x < 0 { x += room.0 }
-        let mut y = (robot.position.1 + robot.velocity.1 * 100) % room.1;
-        while y < 0 { y += room.1 }
-        if x < mid_x && y < mid_y {
-            Some(1)
-        } else if x < mid_x && y > mid_y {
-            Some(2)
-        } else if x > mid_x && y < mid_y {
-            Some(3)
-        } else if x > mid_x && y > mid_y {
-            Some(4)
-        } else {
-            None
+    let mut room = empty_room(size);
+    for robot in &robots {
+        let position = step(robot, 100, &room);
+        room[position] += 1;
+    }
+    let (mid_x, mid_y) = (size.0 / 2, size.1 / 2);
+    let mut quadrants = [0; 4];
+    for (p, &count) in room.cells() {
+        if p.x != mid_x && p.y != mid_y {
+            quadrants[2 * usize::from(p.x > mid_x) + usize::from(p.y > mid_y)] += count;
         }
-    }).fold(HashMap::new(), |mut map, q| {
-        map.entry(q)
-            .and_modify(|e| *e += 1)
-            .or_insert(1);
-        map
-    });
-    quadrants.values().product::<isize>() as usize
+    }
+    quadrants.iter().product()
 }
 
-fn step(robot: &InitialRobot, n: isize, room: XY) -> XY {
-    let mut x = (robot.position.0 + robot.velocity.0 * n) % room.0;
-    while x < 0 { x += room.0 }
-    let mut y = (robot.position.1 + robot.velocity.1 * n) % room.1;
-    while y < 0 { y += room.1 }
-    (x,y)
+fn step(robot: &InitialRobot, n: isize, room: &Room) -> Point {
+    room.wrap(robot.position + robot.velocity * n)
 }
 
-fn print_to_file(file: &mut File, room: (isize, isize), positions: Vec<XY>) {
-    for y in 0..room.1 {
-        for x in 0..room.0 {
-            if positions.iter().any(|p| p.0 == x && p.1 == y) {
-                write!(file, "#").unwrap();
-            } else {
-                write!(file, " ").unwrap();
-            }
-        }
-        writeln!(file).unwrap();
-    }
-    io::stdout().flush().unwrap();
+fn render(room: &Room, positions: &[Point]) -> Image {
+    Image::from_points(positions.iter().copied(), room.width(), room.height(), 4, Rgb::GREEN, Rgb::BLACK)
 }
 
-fn tree_score(positions: &[XY]) -> usize {
-    let mut adjacent_bots = 0;
-    for (index, bot) in positions.iter().enumerate() {
-        for other in positions.iter().skip(index+1) {
-            if bot.0.abs_diff(other.0) <= 1 && bot.1.abs_diff(other.1) <= 1 {
-                adjacent_bots += 1;
-            }
-        }
-    }
-    adjacent_bots
-    // old version
-    // let quadrants = positions.iter().map(|p| {
-    //     let x = p.0;
-    //     if x <= room.0 / 4 {
-    //         1
-    //     } else if x <= room.0 / 2 {
-    //         2
-    //     } else if x <= room.0 * 3 / 4 {
-    //         3
-    //     } else  {
-    //         4
-    //     }
-    // }).fold(HashMap::new(), |mut map, q| {
-    //     map.entry(q)
-    //         .and_modify(|e| *e += 1)
-    //         .or_insert(1usize);
-    //     map
-    // });
-    // 
-    // let dif_1_4 = 1000 * (quadrants[&1].abs_diff(quadrants[&4])) / quadrants[&1];
-    // if dif_1_4 > 100 {
-    //     return 0;
-    // }
-    // let dif_2_3 = 1000 * (quadrants[&2].abs_diff(quadrants[&3])) / quadrants[&2];
-    // if dif_2_3 > 100 {
-    //     return 0
-    // }
-    // // score is dif_1_2
-    // 1000 * (quadrants[&1].abs_diff(quadrants[&2])) / quadrants[&1]
+fn variance(values: impl Iterator<Item = isize> + Clone) -> isize {
+    let count = values.clone().count() as isize;
+    let sum: isize = values.clone().sum();
+    let sum_of_squares: isize = values.map(|v| v * v).sum();
+    // scaled by count^2 so it stays in integers; only the ordering matters
+    count * sum_of_squares - sum * sum
 }
 
-fn part2() {
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("debug_output.txt")
-        .unwrap();
+// x positions repeat every width seconds and y positions every height seconds, so the tree
+// is where each axis is most tightly clustered within its own period, combined with CRT
+fn find_tree(robots: &[InitialRobot], room: &Room) -> isize {
+    let tightest = |axis: fn(Point) -> isize, period: isize| {
+        (0..period).min_by_key(|&seconds| {
+            variance(robots.iter().map(|robot| axis(step(robot, seconds, room))))
+        }).unwrap()
+    };
+    let (width, height) = (room.width() as isize, room.height() as isize);
+    let x_seconds = tightest(|p| p.x, width);
+    let y_seconds = tightest(|p| p.y, height);
+    let (seconds, _) = crt(&[(x_seconds, width), (y_seconds, height)])
+        .expect("Room dimensions share no solution");
+    seconds
+}
+
+fn part2() -> isize {
     let robots = parse("input/2024-14-input.txt");
-    let room = (101, 103);
-    let mut winners = Vec::new();
-    struct Winner {
-        positions: Vec<XY>,
-        score: usize,
-        seconds: isize
-    }
-    let duration = 10000;
-    for seconds in 0..duration {
-        if seconds % (duration / 100) == 0 {
-            println!("{:?} seconds of {:?}", seconds, duration);
-        }
-        let positions = robots.iter().map(|robot| {
-            step(robot, seconds, room)
-        }).collect::<Vec<_>>();
-        let score = tree_score(&positions);
-        let w = Winner {
-            positions, score, seconds
-        };
-        winners.push(w);
-        winners.sort_by_key(|w| w.score);
-        while winners.len() > 10 {
-            winners.remove(0);
-        }
-    }
-    
-    for w in winners {
-        writeln!(file, "---------------------------------------------------------------------------").unwrap();
-        writeln!(file, "Found at {:?} seconds", w.seconds).unwrap();
-        print_to_file(&mut file, room, w.positions)
-    }
+    find_tree(&robots, &empty_room((101, 103)))
+}
+
+// The room at the moment the tree appears, for --ppm
+pub fn tree_image(filename: &str) -> Image {
+    let robots = parse(filename);
+    let room = empty_room((101, 103));
+    let seconds = find_tree(&robots, &room);
+    let positions = robots.iter().map(|robot| step(robot, seconds, &room)).collect::<Vec<_>>();
+    render(&room, &positions)
 }
 
 #[cfg(test)]
@@ -187,9 +118,8 @@ mod tests {
         use crate::year2024day14::part2;
 
         #[test]
-        fn test() {
-            part2()
-            // found it at 8258 seconds
+        fn solution() {
+            assert_eq!(8258, part2());
         }
     }
 }
\ No newline at end of file
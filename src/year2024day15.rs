@@ -1,7 +1,10 @@
 use std::cmp::PartialEq;
+use crate::bitgrid::BitGrid;
+use crate::coord::{Direction, Point};
+use crate::grid::Grid;
 use crate::read_lines;
 
-type Room = Vec<Vec<Tile>>;
+type Room = Grid<Tile>;
 
 #[derive(PartialEq, Eq, Debug)]
 enum Tile {
@@ -11,12 +14,7 @@ enum Tile {
     Robot
 }
 
-enum Move {
-    Up, 
-    Down, 
-    Left, 
-    Right
-}
+type Move = Direction;
 
 fn parse(filename: &str) -> (Room, Vec<Move>) {
     let mut room = Vec::new();
@@ -39,16 +37,14 @@ fn parse(filename: &str) -> (Room, Vec<Move>) {
         }
         room.push(row);
     }
+    let room = Grid::from_rows(room);
 
     let mut moves = Vec::new();
     while let Some(Ok(line)) = lines.next() {
         for c in line.chars() {
-            match c {
-                '^' => moves.push(Move::Up),
-                'v' => moves.push(Move::Down),
-                '<' => moves.push(Move::Left),
-                '>' => moves.push(Move::Right),
-                _ => panic!("unknown move: {}", c)
+            match Direction::parse(c) {
+                Some(m) => moves.push(m),
+                None => panic!("unknown move: {}", c)
             }
         }
     }
@@ -57,40 +53,21 @@ fn parse(filename: &str) -> (Room, Vec<Move>) {
 
 fn part1(filename: &str) -> usize {
     let (mut room, moves) = parse(filename);
-    let mut robot: (isize, isize) = {
-        let mut row = -1;
-        let mut col = -1;
-        for (r, tiles) in room.iter().enumerate() {
-            for (c, tile) in tiles.iter().enumerate() {
-                if let Tile::Robot = tile {
-                    row = r as isize;
-                    col = c as isize;
-                    break;
-                }
-            }
-        }
-        (row, col)
-    };
+    let mut robot = Point::from_cell(room.find(|t| *t == Tile::Robot).unwrap());
     for m in moves {
-        let delta = match m {
-            Move::Up => (-1, 0),
-            Move::Down => (1, 0),
-            Move::Left => (0, -1),
-            Move::Right => (0, 1)
-        };
-        let mut next_position = (robot.0 + delta.0, robot.1 + delta.1);
+        let mut next_position = robot.step(m);
         loop {
-            let next_tile = &room[next_position.0 as usize][next_position.1 as usize];
+            let next_tile = &room[next_position];
             match next_tile {
                 Tile::Wall => break,
                 Tile::Box => {
-                    next_position = (next_position.0 + delta.0, next_position.1 + delta.1)
+                    next_position = next_position.step(m)
                 },
                 Tile::Empty => {
-                    room[next_position.0 as usize][next_position.1 as usize] = Tile::Box;
-                    room[robot.0 as usize][robot.1 as usize] = Tile::Empty;
-                    robot = (robot.0 + delta.0, robot.1 + delta.1);
-                    room[robot.0 as usize][robot.1 as usize] = Tile::Robot;
+                    room[next_position] = Tile::Box;
+                    room[robot] = Tile::Empty;
+                    robot = robot.step(m);
+                    room[robot] = Tile::Robot;
                     break;
                 },
                 _ => panic!("unknown tile {:?}", next_tile)
@@ -98,69 +75,52 @@ fn part1(filename: &str) -> usize {
         }
     }
     // still need to calculate the score
-    let mut score = 0;
-    for (r, tiles) in room.iter().enumerate() {
-        for (c, tile) in tiles.iter().enumerate() {
-            if let Tile::Box = tile {
-                score += (r * 100) + c;
-            }
-        }
-    }
-    score
+    room.find_all(|t| *t == Tile::Box)
+        .map(|(r, c)| (r * 100) + c)
+        .sum()
 }
 
-type XY = (isize, isize);
+type XY = Point;
 
-#[derive(Clone, Eq, PartialEq)]
 struct Box {
     position: XY
 }
 
 impl Box {
-    fn new(position: XY) -> Self {
-        Self { position }
-    }
-    
-    fn contains(&self, position: XY) -> bool {
-        self.position.0 == position.0 && (self.position.1 == position.1 || self.position.1  + 1 == position.1)
-    }
-    
-    fn edges(&self) -> Vec<XY> {
-        vec![self.position, (self.position.0, self.position.1 + 1)]
+    fn edges(&self) -> [XY; 2] {
+        [self.position, self.position.step(Direction::East)]
     }
     
     fn score(&self) -> usize {
-        (100 * self.position.0 + self.position.1) as usize
+        (100 * self.position.y + self.position.x) as usize
     }
 }
 
 struct StretchedRoom {
-    boxes: Vec<Box>,
-    walls: Vec<XY>,
+    // boxes are marked at their left half
+    boxes: BitGrid,
+    walls: BitGrid,
     robot: XY
 }
 
 impl StretchedRoom {
     fn new(room: Room) -> Self {
-        let mut boxes = Vec::new();
-        let mut walls = Vec::new();
-        let mut robot = (-1,-1);
-        for (r, tiles) in room.iter().enumerate() {
-            for (c, tile) in tiles.iter().enumerate() {
-                match tile {
-                    Tile::Box => {
-                        boxes.push(Box::new((r as isize, (c * 2)as isize)));
-                    },
-                    Tile::Wall => {
-                        walls.push((r as isize, (c*2) as isize));
-                        walls.push((r as isize, (c*2 + 1) as isize));
-
-                    },
-                    Tile::Robot => {
-                        robot = (r as isize, (c * 2) as isize);
-                    },
-                    Tile::Empty => {}
-                }
+        let mut boxes = BitGrid::new(room.height(), room.width() * 2);
+        let mut walls = BitGrid::new(room.height(), room.width() * 2);
+        let mut robot = Point::new(-1, -1);
+        for ((r, c), tile) in room.cells() {
+            match tile {
+                Tile::Box => {
+                    boxes.set((r, c * 2));
+                },
+                Tile::Wall => {
+                    walls.set((r, c * 2));
+                    walls.set((r, c * 2 + 1));
+                },
+                Tile::Robot => {
+                    robot = Point::new((c * 2) as isize, r as isize);
+                },
+                Tile::Empty => {}
             }
         }
         Self {
@@ -169,48 +129,53 @@ impl StretchedRoom {
             robot
         }
     }
+
+    fn is_wall(&self, position: XY) -> bool {
+        self.walls.at(position).unwrap_or(true)
+    }
+
+    fn box_at(&self, position: XY) -> Option<Box> {
+        [position, position.step(Direction::West)].into_iter()
+            .find(|&left| self.boxes.at(left) == Some(true))
+            .map(|position| Box { position })
+    }
     
     fn move_robot(&mut self, m: &Move) {
-        let delta = match m { 
-            Move::Up => (-1, 0),
-            Move::Down => (1, 0),
-            Move::Left => (0, -1),
-            Move::Right => (0, 1)
-        };
-        
-        let potential_robot_position = (self.robot.0 + delta.0, self.robot.1 + delta.1);
-        if self.walls.contains(&potential_robot_position) {
+        let potential_robot_position = self.robot.step(*m);
+        if self.is_wall(potential_robot_position) {
             return
         }
         
-        let mut collisions = self.boxes.iter()
-            .filter(|b| b.contains(potential_robot_position))
-            .collect::<Vec<_>>();
+        let mut collisions = self.box_at(potential_robot_position).into_iter().collect::<Vec<_>>();
         let mut moved = Vec::new();
         
         while let Some(b) = collisions.pop() {
+            if moved.contains(&b.position) {
+                continue;
+            }
             for p in b.edges() {
-                let p_moved = (p.0 + delta.0, p.1 + delta.1);
-                if self.walls.contains(&p_moved) { return; }
-                for c in self.boxes.iter().filter(|other| !other.eq(&b) && other.contains(p_moved)) {
-                    collisions.push(c);
+                let p_moved = p.step(*m);
+                if self.is_wall(p_moved) { return; }
+                if let Some(other) = self.box_at(p_moved).filter(|other| other.position != b.position) {
+                    collisions.push(other);
                 }
-                moved.push(b.position);
             }
+            moved.push(b.position);
         }
         
         self.robot = potential_robot_position;
-        for b in self.boxes.iter_mut().filter(|b| moved.contains(&b.position)) {
-            b.position = (b.position.0 + delta.0, b.position.1 + delta.1);
+        for b in &moved {
+            self.boxes.clear(b.to_cell().unwrap());
+        }
+        for b in &moved {
+            self.boxes.set(b.step(*m).to_cell().unwrap());
         }
     }
     
     fn score(&self) -> usize {
-        let mut score = 0;
-        for b in &self.boxes {
-            score += b.score();
-        }
-        score
+        self.boxes.iter()
+            .map(|cell| Box { position: Point::from_cell(cell) }.score())
+            .sum()
     }
 }
 
@@ -230,7 +195,7 @@ mod tests {
         #[test]
         fn test_parse() {
             let (room, moves) = parse("input/2024-15-e2.txt");
-            assert_eq!(8, room.len());
+            assert_eq!(8, room.height());
             assert_eq!(15, moves.len());
         }
     }
@@ -1,49 +1,113 @@
 mod year2024day3 {
-    use regex::Regex;
+    use crate::vm::{Instruction, Operand, Stop, Vm};
 
-    fn part1(line: &str) -> usize {
-        let pattern = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-        let mut result = 0;
-        
-        for m in pattern.find_iter(&line) {
-            let captures = pattern.captures(&m.as_str()).unwrap();
-            let a = captures[1].parse::<usize>().unwrap();
-            let b = captures[2].parse::<usize>().unwrap();
-            result += a * b;
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Token {
+        Mul(i64, i64),
+        Do,
+        Dont,
+    }
+
+    fn number(bytes: &[u8], start: usize) -> Option<(i64, usize)> {
+        let length = bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if length == 0 {
+            return None;
         }
-        
-        result
+        let text = std::str::from_utf8(&bytes[start..start + length]).unwrap();
+        Some((text.parse().ok()?, start + length))
     }
-    
-    fn part2(line: &str) -> usize {
-        let pattern = Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap();
-        let mut result = 0;
-        let mut enabled = true;
-        for m in pattern.find_iter(&line) {
-            match m.as_str() {
-                "don't()" => {
-                    enabled = false;
-                }
-                "do()" => {
-                    enabled = true;
-                }
-                mul => {
-                    if enabled {
-                        let captures = pattern.captures(mul).unwrap();
-                        let a = captures[1].parse::<usize>().unwrap();
-                        let b = captures[2].parse::<usize>().unwrap();
-                        result += a * b;
-                    }
-                    
+
+    // Everything that is not exactly one of the three instructions is corrupted memory
+    fn tokenize(memory: &str) -> Vec<Token> {
+        let bytes = memory.as_bytes();
+        let mul = |start: usize| -> Option<(Token, usize)> {
+            let (a, i) = number(bytes, start + 4)?;
+            (bytes.get(i) == Some(&b',')).then_some(())?;
+            let (b, i) = number(bytes, i + 1)?;
+            (bytes.get(i) == Some(&b')')).then_some((Token::Mul(a, b), i + 1))
+        };
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+            let found = if rest.starts_with(b"mul(") {
+                mul(i)
+            } else if rest.starts_with(b"do()") {
+                Some((Token::Do, i + 4))
+            } else if rest.starts_with(b"don't()") {
+                Some((Token::Dont, i + 7))
+            } else {
+                None
+            };
+            match found {
+                Some((token, next)) => {
+                    tokens.push(token);
+                    i = next;
                 }
+                None => i += 1,
             }
         }
-        
-        result
+        tokens
+    }
+
+    const ENABLED: usize = 0;
+    const TOTAL: usize = 1;
+    const PRODUCT: usize = 2;
+
+    // Products are multiplied by the enabled flag, so disabled ones add nothing
+    fn compile(tokens: &[Token], conditional: bool) -> Vec<Instruction> {
+        let mut program = vec![Instruction::Set(ENABLED, Operand::Immediate(1))];
+        for &token in tokens {
+            match token {
+                Token::Mul(a, b) => program.extend([
+                    Instruction::Set(PRODUCT, Operand::Immediate(a)),
+                    Instruction::Mul(PRODUCT, Operand::Immediate(b)),
+                    Instruction::Mul(PRODUCT, Operand::Register(ENABLED)),
+                    Instruction::Add(TOTAL, Operand::Register(PRODUCT)),
+                ]),
+                Token::Do if conditional => program.push(Instruction::Set(ENABLED, Operand::Immediate(1))),
+                Token::Dont if conditional => program.push(Instruction::Set(ENABLED, Operand::Immediate(0))),
+                _ => {}
+            }
+        }
+        program
+    }
+
+    fn execute(memory: &str, conditional: bool) -> usize {
+        let mut vm = Vm::new(compile(&tokenize(memory), conditional), 3);
+        match vm.run(None) {
+            Stop::Halted => vm.register(TOTAL) as usize,
+            stop => panic!("Program stopped early: {:?}", stop),
+        }
+    }
+
+    fn part1(line: &str) -> usize {
+        execute(line, false)
+    }
+    
+    fn part2(line: &str) -> usize {
+        execute(line, true)
     }
     
     #[cfg(test)]
     mod tests {
+        mod tokens {
+            use crate::vm::disassemble;
+            use crate::year2024day3::year2024day3::{compile, tokenize, Token};
+
+            #[test]
+            fn corrupted() {
+                let tokens = tokenize("mul(4*mul(6,9!?(12,34)don't()mul ( 2 , 4 )mul(11,8)undo()mul(1,)");
+                assert_eq!(vec![Token::Dont, Token::Mul(11, 8), Token::Do], tokens);
+            }
+
+            #[test]
+            fn listing() {
+                let listing = disassemble(&compile(&[Token::Dont, Token::Mul(11, 8)], true));
+                assert_eq!("0  set r0, 1\n1  set r0, 0\n2  set r2, 11\n3  mul r2, 8\n4  mul r2, r0\n5  add r1, r2\n", listing);
+            }
+        }
+
         mod part1 {
             use crate::read_string;
             use crate::year2024day3::year2024day3::part1;
@@ -1,4 +1,5 @@
 mod year2024day5 {
+    use crate::graph::Graph;
     use crate::read_lines;
 
     type Order = Vec<usize>;
@@ -57,16 +58,17 @@ mod year2024day5 {
     
     fn part2(filename: &str) -> usize {
         let input = Input::new(filename);
+        // The full rule set is cyclic, but the rules between the pages of a single order are not
         let fix_and_middle = |order: &Order| -> usize {
-            let mut source = order.clone();
-            let mut dest = Vec::new();
-            while !source.is_empty() {
-                let i = source.iter().position(|&p| {
-                    !input.rules.iter().any(|r| r.left == p && source.contains(&r.right))
-                }).unwrap();
-                dest.push(source.remove(i));
+            let mut graph = Graph::directed();
+            for &page in order {
+                graph.add_node(page);
             }
-            dest[dest.len() / 2]
+            graph.extend(input.rules.iter()
+                .filter(|r| order.contains(&r.left) && order.contains(&r.right))
+                .map(|r| (r.left, r.right)));
+            let fixed = graph.topological_sort().expect("The rules for an order should not be cyclic");
+            fixed[fixed.len() / 2]
         };
         
         input.orders
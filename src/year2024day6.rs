@@ -1,6 +1,10 @@
-mod year2024day6 {
-    use crate::read_lines;
-    use crate::year2024day6::year2024day6::Direction::*;
+pub mod year2024day6 {
+    use crate::bitgrid::BitGrid;
+    use crate::coord::Direction::North;
+    use crate::coord::{Direction, Point};
+    use crate::cycle::find_cycle;
+    use crate::grid::{Cell, Grid};
+    use crate::svg::Svg;
     use crate::year2024day6::year2024day6::TraceError::Loop;
     use std::collections::HashSet;
     use std::sync::mpsc;
@@ -9,111 +13,74 @@ mod year2024day6 {
     struct Floor {
         guard: Position,
         guard_start: Position,
-        obstacles: Vec<Coord>,
+        obstacles: BitGrid,
         path: Vec<Position>,
-        height: isize,
-        width: isize,
     }
 
-    type Coord = (isize, isize);
-
     #[derive(Clone, Copy, Eq, Hash, PartialEq)]
     struct Position {
-        location: Coord,
+        location: Point,
         direction: Direction,
     }
 
     impl Position {
         fn step(&self) -> Self {
-            let l = match &self.direction {
-                UP => (self.location.0 - 1, self.location.1),
-                DOWN => (self.location.0 + 1, self.location.1),
-                RIGHT => (self.location.0, self.location.1 + 1),
-                LEFT => (self.location.0, self.location.1 - 1)
-            };
-            Position { location: l, direction: self.direction }
+            Position { location: self.location.step(self.direction), direction: self.direction }
         }
     }
 
-    #[derive(Clone, Copy, Eq, Hash, PartialEq)]
-    enum Direction {
-        UP, DOWN, LEFT, RIGHT
-    }
-    
     enum TraceError {
         Loop
     }
 
     impl Floor {
         fn new(filename: &str) -> Self {
-            let mut height = 0;
-            let mut width = 0;
-            let mut obstacles = Vec::new();
-            let mut guard_location = (0, 0);
-            let mut lines = read_lines(filename);
-            while let Some(Ok(line)) = lines.next() {
-                width = line.len() as isize;
-                for (i, c) in line.chars().enumerate() {
-                    match c {
-                        '.' => {} // Do nothing
-                        '#' => obstacles.push((height, i as isize)),
-                        '^' => guard_location = (height, i as isize),
-                        c => panic!("Unexpected character {}", c)
-                    }
-                }
-                height += 1;
-            }
-            let guard = Position { location: guard_location, direction: UP };
+            let floor = Grid::read(filename, |c| c);
+            let obstacles = BitGrid::from_grid(&floor, |&c| match c {
+                '.' | '^' => false,
+                '#' => true,
+                c => panic!("Unexpected character {}", c)
+            });
+            let guard_location = Point::from_cell(floor.find(|&c| c == '^').unwrap());
+            let guard = Position { location: guard_location, direction: North };
             Floor {
                 guard: guard.clone(),
                 guard_start: guard.clone(),
                 obstacles,
                 path: vec![guard],
-                height,
-                width
+            }
+        }
+
+        fn next(&self, guard: &Position) -> Option<Position> {
+            let ahead = guard.step();
+            match self.obstacles.at(ahead.location) {
+                None => None,
+                Some(true) => Some(Position { location: guard.location, direction: guard.direction.turn_right() }),
+                Some(false) => Some(ahead),
             }
         }
 
         fn trace_path(&mut self) -> Result<(), TraceError> {
-            let out_of_bounds = |coord: Coord| -> bool {
-                coord.0 < 0 || coord.1 < 0 || coord.0 >= self.height || coord.1 >= self.width
-            };
-            let mut log = HashSet::new();
-            loop {
-                let next_location = self.guard.step();
-                if out_of_bounds(next_location.location) { break }
-                if self.obstacles.iter().any(|&o| o == next_location.location) {
-                    self.guard.direction = match self.guard.direction {
-                        UP => RIGHT,
-                        RIGHT => DOWN,
-                        DOWN => LEFT,
-                        LEFT => UP
-                    };
-                    if !log.insert(self.guard.clone()) {
-                        return Err(Loop);
-                    }
-                    self.path.push(self.guard.clone());
-                } else {
-                    self.guard = next_location;
-                    if !log.insert(self.guard.clone()) {
-                        return Err(Loop);
-                    }
-                    self.path.push(self.guard.clone());
-                }
+            // Walking off the floor settles into None forever, so any other cycle is a loop
+            let cycle = find_cycle(Some(self.guard), |guard| guard.and_then(|g| self.next(&g)));
+            if cycle.start.is_some() {
+                return Err(Loop);
+            }
+            while let Some(next) = self.next(&self.guard) {
+                self.guard = next;
+                self.path.push(next);
             }
             Ok(())
         }
         
-        fn plus_obstacle(&self, obstacle: Coord) -> Self {
+        fn plus_obstacle(&self, obstacle: Cell) -> Self {
             let mut obstacles = self.obstacles.clone();
-            obstacles.push(obstacle);
+            obstacles.set(obstacle);
             Floor {
                 guard: self.guard_start.clone(),
                 guard_start: self.guard_start.clone(),
                 obstacles,
                 path: vec![self.guard_start.clone()],
-                height: self.height,
-                width: self.width
             }
         }
     }
@@ -136,11 +103,11 @@ mod year2024day6 {
         let (tx, rx) = mpsc::channel();
         for &p in floor.path.iter() {
             if p == floor.guard_start { continue }
-            let mut attempt = floor.plus_obstacle(p.location);
+            let mut attempt = floor.plus_obstacle(p.location.to_cell().unwrap());
             let tx1 = tx.clone();
             let _ = thread::spawn(move || {
                 if let Err(Loop) = attempt.trace_path() {
-                    tx1.send(p.location.clone()).unwrap()
+                    tx1.send(p.location).unwrap()
                 }
             });
         }
@@ -152,6 +119,18 @@ mod year2024day6 {
         looping_obstacles.len()
     }
 
+    // Obstacles and the guard's route from the start until they leave the floor
+    pub fn svg(filename: &str) -> Svg {
+        let mut floor = Floor::new(filename);
+        let _ = floor.trace_path();
+        let mut svg = Svg::new(floor.obstacles.width(), floor.obstacles.height(), 12);
+        svg.cells(floor.obstacles.iter().map(Point::from_cell), "#404040");
+        let route = floor.path.iter().map(|p| p.location).collect::<Vec<_>>();
+        svg.polyline(&route, "#d03030", 2.0);
+        svg.label(floor.guard_start.location, &floor.guard_start.direction.to_string(), "#d03030");
+        svg
+    }
+
     #[cfg(test)]
     mod test {
         mod part1 {
@@ -183,7 +162,7 @@ mod year2024day6 {
                     (8,1),
                     (8,3),
                     (9,7)];
-                let actual: HashSet<_> = uut.path.iter().map(|p| p.location).collect();
+                let actual: HashSet<_> = uut.path.iter().map(|p| p.location.to_cell().unwrap()).collect();
                 for c in expected {
                     assert!(actual.contains(&c));
                 }
@@ -206,5 +185,19 @@ mod year2024day6 {
                 assert_eq!(1516, part2("input/2024-06-input.txt"));
             }
         }
+
+        mod svg {
+            use crate::year2024day6::year2024day6::svg;
+
+            #[test]
+            fn example() {
+                let text = svg("input/2024-06-e1.txt").to_string();
+                assert_eq!(8, text.matches(r##"fill="#404040""##).count());
+                assert_eq!(1, text.matches("<polyline").count());
+                // the route starts at the centre of the guard's cell, column 4 row 6
+                assert!(text.contains(r#"<polyline points="54,78 "#));
+                assert!(text.contains(">^</text>"));
+            }
+        }
     }
 }
\ No newline at end of file
@@ -1,14 +1,17 @@
 mod year2024day7 {
     use std::sync::mpsc;
     use std::thread;
-    use regex::Regex;
+    use crate::checked::{ArithmeticError, Checked, CheckedInt};
+    use crate::combinatorics::Product;
+    use crate::parse::{integers, scan, ParseError};
     use crate::read_lines;
     use crate::year2024day7::year2024day7::Operator::*;
 
+    // Generic over the integer width so a larger input can move to u128 unchanged
     #[derive(Debug, PartialEq, Eq)]
-    struct Equation {
-        test_value: usize,
-        operands: Vec<usize>,
+    struct Equation<T = usize> {
+        test_value: T,
+        operands: Vec<T>,
     }
 
     #[derive(Clone, PartialEq, Eq)]
@@ -16,76 +19,66 @@ mod year2024day7 {
         PLUS, TIMES, CONCATENATE
     }
 
-    impl Equation {
-        fn parse(line: &str) -> Self {
-            let captures = Regex::new(r"(\d+): ([\d ]+)").unwrap()
-                .captures(line).unwrap();
-            let test_value = captures[1].parse().unwrap();
-            let operands = captures[2]
-                .split_whitespace()
-                .map(|val| val.parse().unwrap())
-                .collect();
-            Self {
+    const OPERATORS: [Operator; 3] = [PLUS, TIMES, CONCATENATE];
+
+    impl<T: CheckedInt> Equation<T> {
+        fn parse(line: &str) -> Result<Self, ParseError> {
+            let (test_value, operands) = scan!("{}: {}", line => T, String)?;
+            Ok(Self {
                 test_value,
-                operands,
-            }
+                operands: integers(&operands)?,
+            })
         }
 
+        // A candidate that overflows cannot equal the test value, which fits in T, so it just
+        // doesn't match
         fn is_valid(&self, how_many_operators: usize) -> bool {
-            let operators_from_index = |index: usize| -> Vec<Operator> {
-                let mut operators = Vec::new();
-                for i in 0..self.operands.len() - 1 {
-                    match (index / how_many_operators.pow(i as u32)) % how_many_operators {
-                        0 => operators.push(PLUS),
-                        1 => operators.push(TIMES),
-                        2 => operators.push(CONCATENATE),
-                        _ => unreachable!()
-                    }
-                }
-                
-                operators
-            };
-            for index in 0..how_many_operators.pow(self.operands.len() as u32 - 1) {
-                let operators = operators_from_index(index);
-                let mut value = *self.operands.first().unwrap();
-                for i in 0..operators.len() {
-                    match operators[i] {
+            let mut candidates = Product::new(&OPERATORS[..how_many_operators], self.operands.len() - 1);
+            while let Some(operators) = candidates.advance() {
+                let mut value = Checked::new(*self.operands.first().unwrap());
+                for (operator, &operand) in operators.iter().zip(&self.operands[1..]) {
+                    match operator {
                         PLUS => {
-                            value += self.operands[i+1];
+                            value = value + operand;
                         }
                         TIMES => {
-                            value *= self.operands[i+1];
+                            value = value * operand;
                         }
                         CONCATENATE => {
-                            value = format!("{}{}", value, self.operands[i+1]).parse().unwrap();
+                            value = value.concat(operand);
                         }
                     }
                 }
-                if value == self.test_value { return true;}
+                if value.get() == Ok(self.test_value) { return true;}
             }
 
             false
         }
     }
     
-    fn solve(filename: &str, how_many_operators: usize) -> usize {
-        let mut result = 0;
+    fn try_solve<T: CheckedInt + Send + 'static>(filename: &str, how_many_operators: usize) -> Result<T, ArithmeticError> {
+        let mut result = Checked::new(T::ZERO);
         let mut lines = read_lines(filename);
         let (tx, rx) = mpsc::channel();
         while let Some(Ok(line)) = lines.next() {
             let tx1 = tx.clone();
             let _ = thread::spawn(move || {
-                let eq = Equation::parse(line.as_str());
+                let eq = Equation::<T>::parse(line.as_str()).unwrap();
                 if eq.is_valid(how_many_operators) {
                     tx1.send(eq.test_value).unwrap();
                 }
             });
         }
         drop(tx);
+        // the total can still overflow even though every test value fits
         while let Ok(r) = rx.recv() {
-            result += r;
+            result = result + r;
         }
-        result
+        result.get()
+    }
+
+    fn solve(filename: &str, how_many_operators: usize) -> usize {
+        try_solve(filename, how_many_operators).unwrap()
     }
 
     #[cfg(test)]
@@ -95,7 +88,7 @@ mod year2024day7 {
 
             #[test]
             fn parse_line() {
-                let equation = Equation::parse("190: 10 19");
+                let equation = Equation::<usize>::parse("190: 10 19").unwrap();
                 assert_eq!(
                     Equation {
                         test_value: 190,
@@ -111,10 +104,10 @@ mod year2024day7 {
 
             #[test]
             fn single() {
-                assert!(Equation::parse("190: 10 19").is_valid(2));
-                assert!(Equation::parse("3267: 81 40 27").is_valid(2));
-                assert!(Equation::parse("292: 11 6 16 20").is_valid(2));
-                assert!(!Equation::parse("83: 17 5").is_valid(2));
+                assert!(Equation::<usize>::parse("190: 10 19").unwrap().is_valid(2));
+                assert!(Equation::<usize>::parse("3267: 81 40 27").unwrap().is_valid(2));
+                assert!(Equation::<usize>::parse("292: 11 6 16 20").unwrap().is_valid(2));
+                assert!(!Equation::<usize>::parse("83: 17 5").unwrap().is_valid(2));
             }
             
             #[test]
@@ -129,13 +122,13 @@ mod year2024day7 {
         }
         
         mod part2 {
-            use crate::year2024day7::year2024day7::{solve, Equation};
+            use crate::year2024day7::year2024day7::{solve, try_solve, Equation};
             
             #[test]
             fn singles() {
-                assert!(Equation::parse("156: 15 6").is_valid(3));
-                assert!(Equation::parse("7290: 6 8 6 15").is_valid(3));
-                assert!(Equation::parse("192: 17 8 14").is_valid(3));
+                assert!(Equation::<usize>::parse("156: 15 6").unwrap().is_valid(3));
+                assert!(Equation::<usize>::parse("7290: 6 8 6 15").unwrap().is_valid(3));
+                assert!(Equation::<usize>::parse("192: 17 8 14").unwrap().is_valid(3));
             }
 
             #[test]
@@ -143,6 +136,16 @@ mod year2024day7 {
                 assert_eq!(11387, solve("input/2024-07-e1.txt", 3));
             }
 
+            #[test]
+            fn overflow() {
+                // 10000000000 * 10000000000 does not fit in 64 bits, but a later candidate matches
+                let line = "20000000000: 10000000000 10000000000 1";
+                assert!(Equation::<usize>::parse(line).unwrap().is_valid(3));
+                assert!(!Equation::<usize>::parse("30: 10000000000 10000000000").unwrap().is_valid(3));
+                assert!(!Equation::<u128>::parse("30: 10000000000 10000000000").unwrap().is_valid(3));
+                assert_eq!(Ok(11387u128), try_solve("input/2024-07-e1.txt", 3));
+            }
+
             #[test]
             fn solution() {
                 assert_eq!(105517128211543, solve("input/2024-07-input.txt", 3));
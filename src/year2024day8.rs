@@ -1,6 +1,9 @@
-mod year2024day8 {
-    use std::cmp::min;
-    use crate::read_lines;
+pub mod year2024day8 {
+    use crate::combinatorics::pairs;
+    use crate::coord::Point;
+    use crate::grid::Grid;
+    use crate::number_theory::gcd;
+    use crate::svg::Svg;
     use std::collections::HashSet;
 
     type Coord = (isize, isize);
@@ -45,31 +48,20 @@ mod year2024day8 {
         ) -> HashSet<Coord> {
             let mut line = HashSet::new();
 
-            let mut rise = other.location.0 - self.location.0;
-            let mut run = other.location.1 - self.location.1;
-            
-            for i in (1..=min(rise,run)).rev() {
-                if rise.abs() % i == 0 && run.abs() % i == 0 {
-                    rise = rise / i;
-                    run = run / i;
+            // the smallest grid step along the line, in either direction
+            let rise = other.location.0 - self.location.0;
+            let run = other.location.1 - self.location.1;
+            let g = gcd(rise.unsigned_abs(), run.unsigned_abs()) as isize;
+            let (rise, run) = (rise / g, run / g);
+
+            let inside = |(row, col): Coord| row >= 0 && row < height && col >= 0 && col < width;
+            for sign in [1, -1] {
+                let mut location = self.location;
+                while inside(location) {
+                    line.insert(location);
+                    location = (location.0 + sign * rise, location.1 + sign * run);
                 }
             }
-            
-            if rise < 0 {
-                rise *= -1;
-                run *= -1;
-            }
-            
-            let (rise, run) = (rise, run);
-
-            for row in 0..height {
-                if (row - self.location.0) % rise != 0 { continue }
-                let steps = (row - self.location.0) / rise;
-                let delta_run = steps * run;
-                let col = delta_run + self.location.1;
-                if col < 0 || col >= width { continue }
-                line.insert((row, col));
-            }
 
             line
         }
@@ -83,29 +75,18 @@ mod year2024day8 {
 
     impl City {
         fn new(filename: &str) -> Self {
-            let mut height = 0;
-            let mut width = 0;
-            let mut antennas = Vec::new();
-
-            let mut lines = read_lines(filename);
-            while let Some(Ok(line)) = lines.next() {
-                for (i, c) in line.chars().enumerate() {
-                    if !c.is_alphanumeric() {
-                        continue;
-                    }
-                    antennas.push(Antenna {
-                        location: (height, i as isize),
-                        frequency: c,
-                    })
-                }
-                height += 1;
-                width = line.len() as isize;
-            }
+            let map = Grid::read(filename, |c| c);
+            let antennas = map.find_all(|c| c.is_alphanumeric())
+                .map(|(r, c)| Antenna {
+                    location: (r as isize, c as isize),
+                    frequency: map[(r, c)],
+                })
+                .collect();
 
             Self {
                 antennas,
-                height,
-                width,
+                height: map.height() as isize,
+                width: map.width() as isize,
             }
         }
     }
@@ -118,20 +99,13 @@ mod year2024day8 {
         let city = City::new(filename);
         let mut antinodes = HashSet::new();
 
-        for a in &city.antennas {
-            for b in &city.antennas {
-                if a == b {
-                    continue;
-                }
-                if a.frequency != b.frequency {
-                    continue;
-                }
-                let new = match p { 
-                    Part::Part1 => &a.antinodes_with(b),
-                    Part::Part2 => &a.resonance_line_with(b, city.height, city.width)
-                };
-                antinodes = antinodes.union(new).map(|e| *e).collect();
-            }
+        // both antinode rules are symmetric, so each pair only needs looking at once
+        for (a, b) in pairs(&city.antennas).filter(|(a, b)| a.frequency == b.frequency) {
+            let new = match p {
+                Part::Part1 => &a.antinodes_with(b),
+                Part::Part2 => &a.resonance_line_with(b, city.height, city.width)
+            };
+            antinodes = antinodes.union(new).map(|e| *e).collect();
         }
 
         antinodes = antinodes
@@ -144,6 +118,25 @@ mod year2024day8 {
         antinodes.len()
     }
 
+    // Antennas labelled by frequency, each pair's resonance line, and the antinodes it creates
+    pub fn svg(filename: &str) -> Svg {
+        let city = City::new(filename);
+        let to_point = |&(r, c): &Coord| Point::new(c, r);
+        let mut svg = Svg::new(city.width as usize, city.height as usize, 16);
+        for (a, b) in pairs(&city.antennas).filter(|(a, b)| a.frequency == b.frequency) {
+            let mut line = a.resonance_line_with(b, city.height, city.width).into_iter().collect::<Vec<_>>();
+            line.sort();
+            svg.cells(line.iter().map(to_point), "#ffe08a");
+            if let (Some(first), Some(last)) = (line.first(), line.last()) {
+                svg.polyline(&[to_point(first), to_point(last)], "#d08000", 1.5);
+            }
+        }
+        for antenna in &city.antennas {
+            svg.label(to_point(&antenna.location), &antenna.frequency.to_string(), "#203060");
+        }
+        svg
+    }
+
     #[cfg(test)]
     mod tests {
         mod part1 {
@@ -203,6 +196,18 @@ mod year2024day8 {
                 
                 assert_eq!(expected, actual);
             }
+
+            #[test]
+            fn symmetric() {
+                let a = Antenna { frequency: 'a', location: (4, 2) };
+                for location in [(0, 6), (2, 8), (4, 8), (6, 0), (8, 4)] {
+                    let b = Antenna { frequency: 'a', location };
+                    assert_eq!(a.resonance_line_with(&b, 10, 10), b.resonance_line_with(&a, 10, 10));
+                }
+                // same row, one step apart once the common factor is removed
+                let b = Antenna { frequency: 'a', location: (4, 8) };
+                assert_eq!(10, a.resonance_line_with(&b, 10, 10).len());
+            }
             
             #[test]
             fn example1() {
@@ -214,5 +219,17 @@ mod year2024day8 {
                 assert_eq!(1417, analyze("input/2024-08-input.txt", Part2))
             }
         }
+
+        mod svg {
+            use crate::year2024day8::year2024day8::svg;
+
+            #[test]
+            fn example() {
+                let text = svg("input/2024-08-e1.txt").to_string();
+                // one label per antenna and one line per pair sharing a frequency
+                assert_eq!(7, text.matches("<text").count());
+                assert_eq!(6 + 3, text.matches("<polyline").count());
+            }
+        }
     }
 }